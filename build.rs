@@ -0,0 +1,45 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Embeds the files in `content` and `assets/art` in the binary, so it runs
+/// without them on disk. The generated file is included by `src/content.rs`.
+fn main() {
+    println!("cargo:rerun-if-changed=content");
+    println!("cargo:rerun-if-changed=assets/art");
+
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let mut source = String::new();
+
+    embed(&mut source, "QUIZZES", &manifest_dir.join("content").join("quizzes"), &["toml"]);
+    embed(&mut source, "LOCALES", &manifest_dir.join("content").join("locales"), &["toml"]);
+    embed(&mut source, "ART", &manifest_dir.join("assets").join("art"), &["txt", "mask", "ans", "toml"]);
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(out_dir.join("content.rs"), source).unwrap();
+}
+
+/// Declares `name` as the names and contents of the files in `dir` with one
+/// of the `extensions`.
+fn embed(source: &mut String, name: &str, dir: &Path, extensions: &[&str]) {
+    writeln!(source, "pub const {}: &[(&str, &[u8])] = &[", name).unwrap();
+    for path in files(dir, extensions) {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        writeln!(source, "    ({:?}, include_bytes!({:?})),", file_name, path.to_str().unwrap()).unwrap();
+    }
+    source.push_str("];\n");
+}
+
+/// The files in `dir` with one of the `extensions`, sorted by name.
+fn files(dir: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extensions.iter().any(|wanted| extension == *wanted)))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::input::{Chord, Input, Key, MouseButton};
+
+/// Something the player wants to do, independent of which key does it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Confirm,
+    Back,
+    Pause,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Confirm,
+        Action::Back,
+        Action::Pause,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// The name used for the action in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::Confirm => "confirm",
+            Action::Back => "back",
+            Action::Pause => "pause",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Binding {
+    /// A key, whatever modifiers are held with it.
+    Key(Key),
+    /// A key with exactly these modifiers.
+    Chord(Chord),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// Parses the binding names used in the config file: keys like `a` or
+    /// `Left`, chords like `Ctrl+c`, and `MouseLeft`, `MouseRight` or
+    /// `MouseMiddle`.
+    pub fn parse(name: &str) -> Result<Binding, String> {
+        match name.to_lowercase().as_str() {
+            "mouseleft" => return Ok(Binding::Mouse(MouseButton::Left)),
+            "mouseright" => return Ok(Binding::Mouse(MouseButton::Right)),
+            "mousemiddle" => return Ok(Binding::Mouse(MouseButton::Middle)),
+            _ => {}
+        }
+
+        let chord = name.parse::<Chord>()?;
+        if chord.modifiers.is_empty() {
+            Ok(Binding::Key(chord.key))
+        } else {
+            Ok(Binding::Chord(chord))
+        }
+    }
+
+    fn is_pressed(&self, input: &Input) -> bool {
+        match self {
+            Binding::Key(key) => input.is_key_pressed(*key),
+            Binding::Chord(chord) => input.is_chord_pressed(*chord),
+            Binding::Mouse(button) => input.is_mouse_pressed(*button),
+        }
+    }
+
+    fn is_held(&self, input: &Input) -> bool {
+        match self {
+            Binding::Key(key) => input.is_key_held(*key),
+            Binding::Chord(chord) => input.is_chord_held(*chord),
+            Binding::Mouse(button) => input.is_mouse_down(*button),
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{}", key),
+            Binding::Chord(chord) => write!(f, "{}", chord),
+            Binding::Mouse(button) => write!(f, "Mouse{:?}", button),
+        }
+    }
+}
+
+/// Which keys and mouse buttons trigger which actions.
+pub struct ActionMap {
+    bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> ActionMap {
+        let mut map = ActionMap {
+            bindings: HashMap::new(),
+        };
+
+        map.bind(Action::MoveLeft, Binding::Key(Key::Char('a')));
+        map.bind(Action::MoveLeft, Binding::Key(Key::Left));
+        map.bind(Action::MoveRight, Binding::Key(Key::Char('d')));
+        map.bind(Action::MoveRight, Binding::Key(Key::Right));
+        map.bind(Action::MoveUp, Binding::Key(Key::Char('w')));
+        map.bind(Action::MoveUp, Binding::Key(Key::Up));
+        map.bind(Action::MoveDown, Binding::Key(Key::Char('s')));
+        map.bind(Action::MoveDown, Binding::Key(Key::Down));
+        map.bind(Action::Confirm, Binding::Key(Key::Enter));
+        map.bind(Action::Confirm, Binding::Key(Key::Char(' ')));
+        map.bind(Action::Back, Binding::Key(Key::Escape));
+        map.bind(Action::Back, Binding::Key(Key::Backspace));
+        map.bind(Action::Pause, Binding::Key(Key::Char('p')));
+        map.bind(Action::Help, Binding::Key(Key::F(1)));
+        map.bind(Action::Help, Binding::Key(Key::Char('?')));
+        map.bind(Action::Quit, Binding::Key(Key::Char('q')));
+        map.bind(Action::Quit, Binding::Chord(Chord::ctrl('c')));
+
+        map
+    }
+
+    /// The default bindings, with the actions listed in `config` rebound to
+    /// the given keys instead.
+    pub fn from_config(config: &HashMap<String, Vec<String>>) -> Result<ActionMap, String> {
+        let mut map = ActionMap::new();
+
+        for (name, bindings) in config {
+            let action = Action::from_name(name)
+                .ok_or_else(|| format!("unknown action '{}' in bindings", name))?;
+
+            let bindings = bindings
+                .iter()
+                .map(|binding| Binding::parse(binding))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            map.bindings.insert(action, bindings);
+        }
+
+        Ok(map)
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        self.bindings.entry(action).or_default().push(binding);
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The bindings of an action as text for the player, like `a/Left`.
+    pub fn describe(&self, action: Action) -> String {
+        self.bindings(action)
+            .iter()
+            .map(Binding::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn is_pressed(&self, input: &Input, action: Action) -> bool {
+        self.bindings(action).iter().any(|binding| binding.is_pressed(input))
+    }
+
+    pub fn is_held(&self, input: &Input, action: Action) -> bool {
+        self.bindings(action).iter().any(|binding| binding.is_held(input))
+    }
+}
@@ -0,0 +1,230 @@
+use crossterm::style::Color;
+use crate::art::Cell;
+
+/// The characters 0x80 to 0xFF of code page 437, which most `.ans` files are
+/// written in.
+const CP437: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+
+/// The colors of SGR codes 30 to 37 and 40 to 47.
+const COLORS: [Color; 8] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+];
+
+/// The colors of SGR codes 90 to 97 and 100 to 107, and of 30 to 37 in bold.
+const BRIGHT_COLORS: [Color; 8] = [
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// A foreground color, which for the eight basic colors depends on whether
+/// the text is bold.
+#[derive(Clone, Copy)]
+enum Ink {
+    Basic(usize),
+    Exact(Color),
+}
+
+/// The colors the next characters are written in.
+#[derive(Default)]
+struct Pen {
+    foreground: Option<Ink>,
+    background: Option<Color>,
+    bold: bool,
+}
+
+impl Pen {
+    fn cell(&self, c: char) -> Cell {
+        let foreground = self.foreground.map(|ink| match ink {
+            Ink::Basic(i) if self.bold => BRIGHT_COLORS[i],
+            Ink::Basic(i) => COLORS[i],
+            Ink::Exact(color) => color,
+        });
+        Cell { c, foreground, background: self.background }
+    }
+
+    /// Follows the SGR escape code with these parameters, like `1;31`.
+    fn select(&mut self, parameters: &str) -> Result<(), String> {
+        let invalid = || format!("invalid escape code '{}m'", parameters);
+        let codes = parameters
+            .split(';')
+            .map(|code| if code.is_empty() { Ok(0) } else { code.parse::<u8>() })
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| invalid())?;
+
+        let mut codes = codes.into_iter();
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = Pen::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                30..=37 => self.foreground = Some(Ink::Basic(code as usize - 30)),
+                38 => self.foreground = Some(Ink::Exact(extended_color(&mut codes).ok_or_else(invalid)?)),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(COLORS[code as usize - 40]),
+                48 => self.background = Some(extended_color(&mut codes).ok_or_else(invalid)?),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(Ink::Exact(BRIGHT_COLORS[code as usize - 90])),
+                100..=107 => self.background = Some(BRIGHT_COLORS[code as usize - 100]),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The color after a 38 or 48 code, either `5;n` from the 256 color palette
+/// or `2;r;g;b`.
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::AnsiValue(codes.next()?)),
+        2 => Some(Color::Rgb { r: codes.next()?, g: codes.next()?, b: codes.next()? }),
+        _ => None,
+    }
+}
+
+/// Reads ANSI art into rows of cells. `None` is a cell the cursor moved past
+/// without writing anything.
+///
+/// Files that are not UTF-8 are read as code page 437, and anything after the
+/// end of file character, like a SAUCE record, is left out. Rows end at line
+/// breaks. Of the escape codes, colors and moving the cursor forward are
+/// followed, and the rest are skipped.
+pub fn parse(bytes: &[u8]) -> Result<Vec<Vec<Option<Cell>>>, String> {
+    let bytes = match bytes.iter().position(|&byte| byte == 0x1a) {
+        Some(end) => &bytes[..end],
+        None => bytes,
+    };
+    let text: String = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes
+            .iter()
+            .map(|&byte| match byte {
+                0x80.. => CP437.chars().nth(byte as usize - 0x80).unwrap(),
+                _ => byte as char,
+            })
+            .collect(),
+    };
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut pen = Pen::default();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if chars.next() != Some('[') {
+                    continue;
+                }
+                let mut parameters = String::new();
+                let command = loop {
+                    match chars.next() {
+                        Some(c) if c.is_ascii_digit() || c == ';' || c == '?' => parameters.push(c),
+                        Some(c) => break c,
+                        None => return Err("the file ends in the middle of an escape code".to_string()),
+                    }
+                };
+                match command {
+                    'm' => pen.select(&parameters)?,
+                    'C' => row.resize(row.len() + parameters.parse().unwrap_or(1), None),
+                    _ => {}
+                }
+            }
+            '\n' => rows.push(std::mem::take(&mut row)),
+            '\r' => {}
+            c => row.push(Some(pen.cell(c))),
+        }
+    }
+    if !row.is_empty() {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The characters and foreground colors of a row, with `None` for cells
+    /// the cursor moved past.
+    fn foregrounds(row: &[Option<Cell>]) -> Vec<Option<(char, Option<Color>)>> {
+        row.iter().map(|cell| cell.map(|cell| (cell.c, cell.foreground))).collect()
+    }
+
+    #[test]
+    fn bold_makes_basic_colors_bright() {
+        let rows = parse(b"\x1b[31ma\x1b[1mb\x1b[22mc\x1b[1;94md\x1b[0me").unwrap();
+
+        assert_eq!(foregrounds(&rows[0]), vec![
+            Some(('a', Some(Color::DarkRed))),
+            Some(('b', Some(Color::Red))),
+            Some(('c', Some(Color::DarkRed))),
+            Some(('d', Some(Color::Blue))),
+            Some(('e', None)),
+        ]);
+    }
+
+    #[test]
+    fn reads_extended_colors() {
+        let rows = parse(b"\x1b[38;5;208ma\x1b[38;2;1;2;3;48;5;17mb").unwrap();
+
+        assert_eq!(rows[0][0].unwrap().foreground, Some(Color::AnsiValue(208)));
+        let b = rows[0][1].unwrap();
+        assert_eq!(b.foreground, Some(Color::Rgb { r: 1, g: 2, b: 3 }));
+        assert_eq!(b.background, Some(Color::AnsiValue(17)));
+    }
+
+    #[test]
+    fn rejects_unfinished_extended_colors() {
+        assert!(parse(b"\x1b[38;2;1;2ma").is_err());
+        assert!(parse(b"\x1b[48;7ma").is_err());
+        assert!(parse(b"\x1b[300ma").is_err());
+    }
+
+    #[test]
+    fn cursor_forward_leaves_cells_empty() {
+        let rows = parse(b"a\x1b[3Cb\x1b[Cc\r\nd").unwrap();
+
+        assert_eq!(foregrounds(&rows[0]), vec![
+            Some(('a', None)),
+            None,
+            None,
+            None,
+            Some(('b', None)),
+            None,
+            Some(('c', None)),
+        ]);
+        assert_eq!(foregrounds(&rows[1]), vec![Some(('d', None))]);
+    }
+
+    #[test]
+    fn reads_files_that_are_not_utf8_as_cp437() {
+        let rows = parse(&[b'a', 0xdb, 0xb0, 0x80, 0xff]).unwrap();
+
+        let text: String = rows[0].iter().map(|cell| cell.unwrap().c).collect();
+        assert_eq!(text, "a█░Ç\u{a0}");
+    }
+
+    #[test]
+    fn stops_at_the_end_of_file_character() {
+        let mut bytes = b"ab\ncd".to_vec();
+        bytes.push(0x1a);
+        bytes.extend_from_slice(b"SAUCE00 title\x1b[");
+
+        let rows = parse(&bytes).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(foregrounds(&rows[1]), vec![Some(('c', None)), Some(('d', None))]);
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+use std::ops::Index;
+use crossterm::style::Color;
+use serde::Deserialize;
+use crate::ansi;
+use crate::screen::{Screen, Style};
+
+/// What is known about a piece of art besides how it looks, read from a
+/// `.toml` file beside it.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Metadata {
+    /// Points in the art, as `name = [x, y]` from its top left corner.
+    #[serde(default)]
+    anchors: BTreeMap<String, (i16, i16)>,
+    /// Areas of the art, as `name = [x, y, width, height]` from its top left
+    /// corner.
+    #[serde(default)]
+    hotspots: BTreeMap<String, (u16, u16, u16, u16)>,
+    /// What each letter in the `.mask` file stands for.
+    #[serde(default)]
+    palette: BTreeMap<char, Paint>,
+    /// Characters that are not drawn, so what is behind them shows through,
+    /// unless the mask gives them a background or makes them opaque.
+    #[serde(default = "default_transparent")]
+    transparent: Vec<char>,
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Metadata {
+            anchors: BTreeMap::new(),
+            hotspots: BTreeMap::new(),
+            palette: BTreeMap::new(),
+            transparent: default_transparent(),
+        }
+    }
+}
+
+fn default_transparent() -> Vec<char> {
+    vec![' ']
+}
+
+/// How the characters under a letter of the mask are drawn: `"transparent"`
+/// or `"opaque"`, a color like `"red"` or `"#1e7a2e"`, or a table with a
+/// `foreground` and a `background` color.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(untagged, expecting = "expected \"transparent\", \"opaque\", a color, or a table with a foreground and a background color")]
+enum Paint {
+    Rule(Rule),
+    Color(Color),
+    Colors {
+        foreground: Option<Color>,
+        background: Option<Color>,
+    },
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Rule {
+    /// Not drawn, whatever the character is.
+    Transparent,
+    /// Drawn even if the character is one of the transparent ones.
+    Opaque,
+}
+
+/// A character of art, and its colors. Characters without a color are drawn
+/// in the color the art is drawn with.
+#[derive(Clone, Copy)]
+pub struct Cell {
+    pub c: char,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+/// A picture made of characters, read from a `.txt` file in `assets/art`,
+/// optionally colored by a `.mask` file, or from an `.ans` file with ANSI
+/// colors. Spaces are transparent, unless the metadata says otherwise.
+///
+/// Art is placed by one of its anchors. Every piece of art has the anchors
+/// `top_left`, `top`, `top_right`, `left`, `center`, `right`, `bottom_left`,
+/// `bottom` and `bottom_right`, and can name more in its metadata.
+pub struct Art {
+    /// The cells of each row. `None` is transparent.
+    rows: Vec<Vec<Option<Cell>>>,
+    width: u16,
+    height: u16,
+    metadata: Metadata,
+}
+
+impl Art {
+    /// Reads art from the text of its `.txt` file, and of its `.mask` and
+    /// `.toml` files if it has them.
+    ///
+    /// The mask is laid out like the art, and each letter in it colors the
+    /// character in the same place by the palette in the metadata. Spaces in
+    /// the mask leave the character as it is.
+    pub fn parse(text: &str, mask: Option<&str>, metadata: Option<&str>) -> Result<Art, String> {
+        let metadata = parse_metadata(metadata)?;
+
+        let text: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+        let mask: Vec<Vec<char>> = mask.unwrap_or("").lines().map(|line| line.chars().collect()).collect();
+
+        let mut rows = Vec::new();
+        for y in 0..text.len().max(mask.len()) {
+            let glyphs = text.get(y).map_or(&[][..], Vec::as_slice);
+            let letters = mask.get(y).map_or(&[][..], Vec::as_slice);
+
+            let mut row = Vec::new();
+            for x in 0..glyphs.len().max(letters.len()) {
+                let c = glyphs.get(x).copied().unwrap_or(' ');
+                let paint = match letters.get(x).copied().unwrap_or(' ') {
+                    ' ' => None,
+                    letter => Some(*metadata.palette.get(&letter).ok_or_else(|| {
+                        format!("the mask has '{}' at row {}, column {}, which is not in the palette", letter, y + 1, x + 1)
+                    })?),
+                };
+
+                let cell = match paint {
+                    Some(Paint::Rule(Rule::Transparent)) => None,
+                    Some(Paint::Rule(Rule::Opaque)) => Some(Cell { c, foreground: None, background: None }),
+                    Some(Paint::Color(color)) => Some(Cell { c, foreground: Some(color), background: None }),
+                    Some(Paint::Colors { foreground, background }) => Some(Cell { c, foreground, background }),
+                    None => Some(Cell { c, foreground: None, background: None }),
+                };
+                let opaque = matches!(paint, Some(Paint::Rule(Rule::Opaque)));
+                row.push(cell.filter(|cell| opaque || is_visible(cell, &metadata)));
+            }
+            rows.push(row);
+        }
+
+        Art::new(rows, metadata)
+    }
+
+    /// Reads art from an `.ans` file, with the colors it sets with ANSI escape
+    /// codes, and from the text of its `.toml` file if it has one.
+    pub fn parse_ansi(bytes: &[u8], metadata: Option<&str>) -> Result<Art, String> {
+        let metadata = parse_metadata(metadata)?;
+        if !metadata.palette.is_empty() {
+            return Err("only art with a .mask file has a palette".to_string());
+        }
+
+        let rows = ansi::parse(bytes)?
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.filter(|cell| is_visible(cell, &metadata))).collect())
+            .collect();
+        Art::new(rows, metadata)
+    }
+
+    fn new(rows: Vec<Vec<Option<Cell>>>, metadata: Metadata) -> Result<Art, String> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0) as u16;
+        let height = rows.len() as u16;
+
+        for (name, &(x, y, w, h)) in &metadata.hotspots {
+            // Widened, since the numbers come from the metadata and can be
+            // anything.
+            if x as u32 + w as u32 > width as u32 || y as u32 + h as u32 > height as u32 {
+                return Err(format!("hotspot '{}' is outside the art, which is {}x{}", name, width, height));
+            }
+        }
+
+        Ok(Art { rows, width, height, metadata })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The point named `name`, from the top left corner of the art.
+    pub fn anchor(&self, name: &str) -> Option<(i16, i16)> {
+        if let Some(&point) = self.metadata.anchors.get(name) {
+            return Some(point);
+        }
+
+        let (right, bottom) = (self.width as i16 - 1, self.height as i16 - 1);
+        let (middle, center) = (bottom / 2, right / 2);
+        match name {
+            "top_left" => Some((0, 0)),
+            "top" => Some((center, 0)),
+            "top_right" => Some((right, 0)),
+            "left" => Some((0, middle)),
+            "center" => Some((center, middle)),
+            "right" => Some((right, middle)),
+            "bottom_left" => Some((0, bottom)),
+            "bottom" => Some((center, bottom)),
+            "bottom_right" => Some((right, bottom)),
+            _ => None,
+        }
+    }
+
+    /// Where the top left corner goes for `anchor` to end up at `x`, `y`.
+    /// Art without the anchor is placed by its top left corner. Anchors named
+    /// in content files, and the ones the days use, are checked when the
+    /// files are read.
+    pub fn origin(&self, anchor: &str, x: i16, y: i16) -> (i16, i16) {
+        let (anchor_x, anchor_y) = self.anchor(anchor).unwrap_or((0, 0));
+        (x - anchor_x, y - anchor_y)
+    }
+
+    /// Draws the art with `anchor` at `x`, `y`, in white where it has no
+    /// colors of its own.
+    pub fn draw(&self, screen: &mut Screen, anchor: &str, x: i16, y: i16) {
+        self.draw_colored(screen, anchor, x, y, Color::White);
+    }
+
+    /// Draws the art with `anchor` at `x`, `y`. Characters the art gives no
+    /// color are drawn in `color`.
+    pub fn draw_colored(&self, screen: &mut Screen, anchor: &str, x: i16, y: i16, color: Color) {
+        let (x, y) = self.origin(anchor, x, y);
+        for (row, cells) in self.rows.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                let Some(cell) = cell else {
+                    continue;
+                };
+                let style = Style::new(cell.foreground.unwrap_or(color)).background(cell.background.unwrap_or(Color::Reset));
+                screen.set_cell_safe(x + column as i16, y + row as i16, cell.c, style);
+            }
+        }
+    }
+
+    pub fn has_hotspot(&self, name: &str) -> bool {
+        self.metadata.hotspots.contains_key(name)
+    }
+
+    /// Whether `point` on the screen is inside the hotspot named `name`, with
+    /// the art's top left corner at `origin`. Art without the hotspot has
+    /// nothing inside it.
+    pub fn hotspot_contains(&self, name: &str, origin: (i16, i16), point: (u16, u16)) -> bool {
+        let Some(&(x, y, w, h)) = self.metadata.hotspots.get(name) else {
+            return false;
+        };
+        let (left, top) = (origin.0 + x as i16, origin.1 + y as i16);
+        let (px, py) = (point.0 as i16, point.1 as i16);
+        px >= left && px < left + w as i16 && py >= top && py < top + h as i16
+    }
+}
+
+fn parse_metadata(metadata: Option<&str>) -> Result<Metadata, String> {
+    match metadata {
+        Some(metadata) => toml::from_str(metadata).map_err(|error| error.to_string()),
+        None => Ok(Metadata::default()),
+    }
+}
+
+/// Whether `cell` is drawn: characters that are transparent by the metadata
+/// are only drawn when they have a background.
+fn is_visible(cell: &Cell, metadata: &Metadata) -> bool {
+    cell.background.is_some() || !metadata.transparent.contains(&cell.c)
+}
+
+/// Every piece of art, by the name of its file without the extension.
+///
+/// The art in `assets/art` is built in, and art read at runtime replaces the
+/// built in art of the same name. So art the code asks for by name is always
+/// there, and can be looked up with `library["santa"]`.
+#[derive(Default)]
+pub struct ArtLibrary {
+    art: BTreeMap<String, Art>,
+}
+
+impl ArtLibrary {
+    pub fn new() -> ArtLibrary {
+        ArtLibrary { art: BTreeMap::new() }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Art> {
+        self.art.get(name)
+    }
+
+    /// Adds `art`, replacing any art with the same name.
+    pub fn insert(&mut self, name: String, art: Art) {
+        self.art.insert(name, art);
+    }
+}
+
+impl Index<&str> for ArtLibrary {
+    type Output = Art;
+
+    fn index(&self, name: &str) -> &Art {
+        self.get(name).unwrap_or_else(|| panic!("there is no art named '{}'", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The characters of a row, with `.` for transparent cells.
+    fn text(art: &Art, y: usize) -> String {
+        art.rows[y].iter().map(|cell| cell.map_or('.', |cell| cell.c)).collect()
+    }
+
+    #[test]
+    fn spaces_are_transparent() {
+        let art = Art::parse("a b\n  c", None, None).unwrap();
+
+        assert_eq!((art.width(), art.height()), (3, 2));
+        assert_eq!(text(&art, 0), "a.b");
+        assert_eq!(text(&art, 1), "..c");
+    }
+
+    #[test]
+    fn the_mask_colors_by_the_palette() {
+        let metadata = "[palette]\nr = \"red\"\ns = { foreground = \"white\", background = \"#1e7a2e\" }";
+        let art = Art::parse("ab c", Some("r s"), Some(metadata)).unwrap();
+
+        let cells = &art.rows[0];
+        assert_eq!(cells[0].unwrap().foreground, Some(Color::Red));
+        assert_eq!(cells[1].unwrap().foreground, None);
+        assert_eq!(cells[2].unwrap().c, ' ');
+        assert_eq!(cells[2].unwrap().foreground, Some(Color::White));
+        assert_eq!(cells[2].unwrap().background, Some(Color::Rgb { r: 0x1e, g: 0x7a, b: 0x2e }));
+        assert!(cells[3].unwrap().foreground.is_none());
+    }
+
+    #[test]
+    fn the_mask_can_hide_and_show_characters() {
+        let metadata = "transparent = [\" \", \"~\"]\n[palette]\n_ = \"transparent\"\no = \"opaque\"";
+        let art = Art::parse("ab~~ d", Some("_   o"), Some(metadata)).unwrap();
+
+        assert_eq!(text(&art, 0), ".b.. d");
+    }
+
+    #[test]
+    fn the_mask_can_be_larger_than_the_art() {
+        let art = Art::parse("a", Some(" r\nr"), Some("[palette]\nr = { background = \"red\" }")).unwrap();
+
+        assert_eq!((art.width(), art.height()), (2, 2));
+        assert_eq!(text(&art, 0), "a ");
+        assert_eq!(text(&art, 1), " ");
+    }
+
+    #[test]
+    fn mask_letters_must_be_in_the_palette() {
+        let error = Art::parse("ab\ncd", Some("\n x"), None).err().unwrap();
+        assert_eq!(error, "the mask has 'x' at row 2, column 2, which is not in the palette");
+    }
+
+    #[test]
+    fn hotspots_must_be_inside_the_art() {
+        assert!(Art::parse("abc", None, Some("[hotspots]\nall = [0, 0, 3, 1]")).is_ok());
+        assert!(Art::parse("abc", None, Some("[hotspots]\nwide = [1, 0, 3, 1]")).is_err());
+        assert!(Art::parse("abc", None, Some("[hotspots]\nhuge = [1, 0, 65535, 1]")).is_err());
+    }
+
+    #[test]
+    fn art_without_the_anchor_is_placed_by_its_top_left_corner() {
+        let art = Art::parse("abc\ndef", None, Some("[anchors]\nmouth = [1, 1]")).unwrap();
+
+        assert_eq!(art.origin("mouth", 10, 10), (9, 9));
+        assert_eq!(art.origin("bottom_right", 10, 10), (8, 9));
+        assert_eq!(art.origin("nowhere", 10, 10), (10, 10));
+    }
+}
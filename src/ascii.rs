@@ -1,59 +0,0 @@
-pub const SANTA: &str = r#"
-                    _...
-              o_.-"`    `\
-       .--.  _ `'-._.-'""-;     _
-     .'    \`_\_  {_.-a"a-}  _ / \
-   _/     .-'  '. {c-._o_.){\|`  |
-  (@`-._ /       \{    ^  } \\ _/
-   `~\  '-._      /'.     }  \}  .-.
-     |>:<   '-.__/   '._,} \_/  / ())
-     |     >:<   `'---. ____'-.|(`"`
-     \            >:<  \\_\\_\ | ;
-      \                 \\-{}-\/  \
-       \                 '._\\'   /)
-        '.                       /(
-          `-._ _____ _ _____ __.'\ \
-            / \     / \     / \   \ \
-         _.'/^\'._.'/^\'._.'/^\'.__) \
-     ,=='  `---`   '---'   '---'      )
-     `"""""""""""""""""""""""""""""""`"#;
-
-pub const SYSTEK: &str = r#"
-     ██             ███████                 ██            ██
-   ████            ██     ██                ██            ██
- ██████            ███     ██    ████████████████ ██████  ██  ███
-██████▓             ██████ ██    ████       ██   ██    ██ █████
-  ████▓▓▒                ██ ██  ██  █████   ██  █████████ ██████
-    ██▓▒▒▒▒               ██ █████      ███ ██  ███       ██  ███
-     █▓▒▒▒▒▒▒     ███    ███ ████   ██   ██ ██   ███      ██   ███
-      ▒▒▒▒▒▒        ██████    ██     █████   ████  ████   ██    ██
-      ▒▒▒▒                   ██
-      ▒▒                     ██"#;
-
-
-pub const XMAS_TREE: &str = r#"
-                         ,
-                       _/^\_
-                      <     >
-                       /.-.\
-                       `/&\`
-                      ,@.*;@,
-                     /_o.I %_\
-                    (`'--:o(_@;
-                   /`;--.,__ `')
-                  ;@`o % O,*`'`&\
-                 (`'--)_@ ;o %'()\
-                 /`;--._`''--._O'@;
-                /&*,()~o`;-.,_ `""`)
-                /`,@ ;+& () o*`;-';\
-               (`""--.,_0 +% @' &()\
-               /-.,_    ``''--....-'`)
-               /@%;o`:;'--,.__   __.'\
-              ;*,&(); @ % &^;~`"`o;@();
-              /(); o^~; & ().o@*&`;&%O\
-              `"="==""==,,,.,="=="==="`
-           __.----.(\-''#####---...___...-----._
-         '`         \)_`"""""`
-                 .--' ')
-               o(  )_-\
-                 `"""` `"#;
\ No newline at end of file
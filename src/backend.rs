@@ -0,0 +1,377 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Stdout, Write};
+use std::rc::Rc;
+use std::time::Duration;
+use crossterm::{cursor, event, queue, style, terminal};
+use crossterm::cursor::MoveTo;
+use crossterm::event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags};
+use crossterm::style::{Attribute, Attributes, ResetColor, SetAttribute, SetAttributes, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType};
+#[cfg(test)]
+use crate::screen::{Cell, Style};
+
+/// Everything `Screen` needs from the terminal it draws to.
+pub trait Backend {
+    fn init(&mut self) -> Result<(), Error>;
+    fn cleanup(&mut self) -> Result<(), Error>;
+    fn size(&self) -> Result<(u16, u16), Error>;
+    /// Whether the terminal reports key releases, so keys can be tracked as
+    /// held until they are actually let go.
+    fn reports_key_releases(&self) -> bool;
+    fn clear(&mut self) -> Result<(), Error>;
+    fn move_to(&mut self, x: u16, y: u16) -> Result<(), Error>;
+    /// Replaces the current attributes. Like on a real terminal, this also
+    /// resets the foreground and background colors.
+    fn set_attributes(&mut self, attributes: Attributes) -> Result<(), Error>;
+    fn set_foreground(&mut self, color: style::Color) -> Result<(), Error>;
+    fn set_background(&mut self, color: style::Color) -> Result<(), Error>;
+    fn print(&mut self, text: &str) -> Result<(), Error>;
+    fn flush(&mut self) -> Result<(), Error>;
+}
+
+/// Everything `Input` needs to receive events from the terminal.
+pub trait EventSource {
+    fn poll(&mut self, timeout: Duration) -> Result<bool, Error>;
+    fn read(&mut self) -> Result<Event, Error>;
+}
+
+pub struct CrosstermBackend {
+    stdout: Stdout,
+    keyboard_enhanced: bool,
+}
+
+impl CrosstermBackend {
+    pub fn new(stdout: Stdout) -> CrosstermBackend {
+        CrosstermBackend {
+            stdout,
+            keyboard_enhanced: false,
+        }
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn init(&mut self) -> Result<(), Error> {
+        enable_raw_mode()?;
+
+        queue!(
+            self.stdout,
+            terminal::EnterAlternateScreen,
+            cursor::Hide,
+            EnableMouseCapture,
+            EnableFocusChange,
+            Clear(ClearType::All),
+        )?;
+
+        // Terminals implementing the kitty keyboard protocol can report key
+        // releases and repeats, which is the only way to know a key is held.
+        self.keyboard_enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if self.keyboard_enhanced {
+            queue!(
+                self.stdout,
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                ),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn cleanup(&mut self) -> Result<(), Error> {
+        if self.keyboard_enhanced {
+            queue!(self.stdout, PopKeyboardEnhancementFlags)?;
+            self.keyboard_enhanced = false;
+        }
+
+        disable_raw_mode()?;
+
+        queue!(
+            self.stdout,
+            SetAttribute(Attribute::Reset),
+            ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableFocusChange,
+        )?;
+
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    fn size(&self) -> Result<(u16, u16), Error> {
+        terminal::size()
+    }
+
+    fn reports_key_releases(&self) -> bool {
+        // The Windows console always reports releases.
+        self.keyboard_enhanced || cfg!(windows)
+    }
+
+    fn clear(&mut self) -> Result<(), Error> {
+        queue!(self.stdout, Clear(ClearType::All))
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> Result<(), Error> {
+        queue!(self.stdout, MoveTo(x, y))
+    }
+
+    fn set_attributes(&mut self, attributes: Attributes) -> Result<(), Error> {
+        queue!(self.stdout, SetAttribute(Attribute::Reset), SetAttributes(attributes))
+    }
+
+    fn set_foreground(&mut self, color: style::Color) -> Result<(), Error> {
+        queue!(self.stdout, SetForegroundColor(color))
+    }
+
+    fn set_background(&mut self, color: style::Color) -> Result<(), Error> {
+        queue!(self.stdout, SetBackgroundColor(color))
+    }
+
+    fn print(&mut self, text: &str) -> Result<(), Error> {
+        queue!(self.stdout, style::Print(text))
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.stdout.flush()
+    }
+}
+
+pub struct CrosstermEvents;
+
+impl EventSource for CrosstermEvents {
+    fn poll(&mut self, timeout: Duration) -> Result<bool, Error> {
+        event::poll(timeout)
+    }
+
+    fn read(&mut self) -> Result<Event, Error> {
+        event::read()
+    }
+}
+
+/// The in-memory contents of a `HeadlessBackend`, as a terminal would show it.
+#[cfg(test)]
+pub struct HeadlessTerminal {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    cursor: (u16, u16),
+    style: Style,
+    flushes: usize,
+}
+
+#[cfg(test)]
+impl HeadlessTerminal {
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn cell(&self, x: u16, y: u16) -> &Cell {
+        &self.cells[(y as usize) * (self.width as usize) + x as usize]
+    }
+
+    pub fn line(&self, y: u16) -> String {
+        (0..self.width).map(|x| self.cell(x, y).rune).collect()
+    }
+
+    pub fn contents(&self) -> String {
+        (0..self.height).map(|y| self.line(y)).collect::<Vec<_>>().join("\n")
+    }
+
+    /// How many times the backend has been flushed, which is once per render.
+    pub fn flushes(&self) -> usize {
+        self.flushes
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        self.cells = vec![Cell::new(' ', Style::default()); width as usize * height as usize];
+        self.cursor = (0, 0);
+    }
+}
+
+/// A backend that renders into memory instead of a terminal, so `Screen` can be
+/// used without a TTY in tests. The contents can be inspected through
+/// `terminal()`.
+#[cfg(test)]
+pub struct HeadlessBackend {
+    terminal: Rc<RefCell<HeadlessTerminal>>,
+}
+
+#[cfg(test)]
+impl HeadlessBackend {
+    pub fn new((width, height): (u16, u16)) -> HeadlessBackend {
+        let mut terminal = HeadlessTerminal {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            cursor: (0, 0),
+            style: Style::default(),
+            flushes: 0,
+        };
+        terminal.resize(width, height);
+
+        HeadlessBackend {
+            terminal: Rc::new(RefCell::new(terminal)),
+        }
+    }
+
+    /// A handle to the rendered contents, which stays valid after the backend
+    /// has been moved into a `Screen`.
+    pub fn terminal(&self) -> Rc<RefCell<HeadlessTerminal>> {
+        self.terminal.clone()
+    }
+}
+
+#[cfg(test)]
+impl Backend for HeadlessBackend {
+    fn init(&mut self) -> Result<(), Error> {
+        self.clear()
+    }
+
+    fn cleanup(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn size(&self) -> Result<(u16, u16), Error> {
+        Ok(self.terminal.borrow().size())
+    }
+
+    /// Scripted input is expected to contain key releases, like a terminal
+    /// with keyboard enhancement.
+    fn reports_key_releases(&self) -> bool {
+        true
+    }
+
+    fn clear(&mut self) -> Result<(), Error> {
+        let mut terminal = self.terminal.borrow_mut();
+        let (width, height) = terminal.size();
+        terminal.resize(width, height);
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: u16, y: u16) -> Result<(), Error> {
+        self.terminal.borrow_mut().cursor = (x, y);
+        Ok(())
+    }
+
+    fn set_attributes(&mut self, attributes: Attributes) -> Result<(), Error> {
+        let mut terminal = self.terminal.borrow_mut();
+        terminal.style = Style::new(style::Color::Reset);
+        terminal.style.attributes = attributes;
+        Ok(())
+    }
+
+    fn set_foreground(&mut self, color: style::Color) -> Result<(), Error> {
+        self.terminal.borrow_mut().style.foreground = color;
+        Ok(())
+    }
+
+    fn set_background(&mut self, color: style::Color) -> Result<(), Error> {
+        self.terminal.borrow_mut().style.background = color;
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str) -> Result<(), Error> {
+        let mut terminal = self.terminal.borrow_mut();
+        let style = terminal.style;
+
+        for rune in text.chars() {
+            let (x, y) = terminal.cursor;
+            if x < terminal.width && y < terminal.height {
+                let index = (y as usize) * (terminal.width as usize) + x as usize;
+                terminal.cells[index] = Cell::new(rune, style);
+            }
+            terminal.cursor = (x.saturating_add(1), y);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.terminal.borrow_mut().flushes += 1;
+        Ok(())
+    }
+}
+
+/// An event source fed from a script instead of the terminal. Events are added
+/// through the queue returned by `queue()`, also after the source has been
+/// handed to `Input`.
+pub struct ScriptedEvents {
+    queue: Rc<RefCell<VecDeque<Event>>>,
+}
+
+impl ScriptedEvents {
+    pub fn new() -> ScriptedEvents {
+        ScriptedEvents {
+            queue: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    pub fn queue(&self) -> Rc<RefCell<VecDeque<Event>>> {
+        self.queue.clone()
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn poll(&mut self, _timeout: Duration) -> Result<bool, Error> {
+        Ok(!self.queue.borrow().is_empty())
+    }
+
+    fn read(&mut self) -> Result<Event, Error> {
+        self.queue
+            .borrow_mut()
+            .pop_front()
+            .ok_or_else(|| Error::new(ErrorKind::WouldBlock, "no scripted events left"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Color;
+    use crate::screen::{Screen, Style};
+    use super::*;
+
+    #[test]
+    fn renders_screen_into_memory() {
+        let backend = HeadlessBackend::new((81, 40));
+        let terminal = backend.terminal();
+        let mut screen = Screen::new(Box::new(backend), (81, 40));
+        screen.init().unwrap();
+
+        screen.draw_text(2, 1, "God jul", Style::new(Color::Red).bold());
+        screen.render();
+
+        let terminal = terminal.borrow();
+        assert_eq!(terminal.size(), (81, 40));
+        assert_eq!(terminal.line(1).trim_end(), "  God jul");
+        assert!(terminal.cell(2, 1) == &Cell::new('G', Style::new(Color::Red).bold()));
+        assert!(terminal.cell(0, 0) == &Cell::new(' ', Style::default()));
+        assert_eq!(terminal.flushes(), 1);
+    }
+
+    #[test]
+    fn renders_only_changes_over_previous_frame() {
+        let backend = HeadlessBackend::new((81, 40));
+        let terminal = backend.terminal();
+        let mut screen = Screen::new(Box::new(backend), (81, 40));
+        screen.init().unwrap();
+
+        screen.draw_text(0, 0, "luke 1", Color::White);
+        screen.render();
+
+        // Cells the screen thinks are unchanged are not written again, so
+        // this stays on the terminal.
+        terminal.borrow_mut().cells[10 * 81 + 50] = Cell::new('*', Style::default());
+
+        screen.clear();
+        screen.draw_text(0, 0, "luke 2", Color::White);
+        screen.render();
+
+        let terminal = terminal.borrow();
+        assert!(terminal.contents().starts_with("luke 2 "));
+        assert_eq!(terminal.line(10).trim(), "*");
+        assert_eq!(terminal.flushes(), 2);
+    }
+}
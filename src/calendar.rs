@@ -0,0 +1,100 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use crate::config::CalendarConfig;
+
+/// Which doors of the calendar can be opened. A door opens on its day in
+/// December, going by the date in the configured time zone, and stays open
+/// after Christmas. The doors only close again when the next December comes,
+/// and the calendar starts over.
+pub struct Calendar {
+    timezone: Tz,
+    unlock_all: bool,
+    /// Used instead of the real date, so replays see the day they were
+    /// recorded on.
+    date: Option<NaiveDate>,
+}
+
+impl Calendar {
+    pub fn new(config: &CalendarConfig) -> Result<Calendar, String> {
+        let timezone = config
+            .timezone
+            .parse()
+            .map_err(|_| format!("unknown time zone '{}'", config.timezone))?;
+
+        Ok(Calendar {
+            timezone,
+            unlock_all: config.unlock_all,
+            date: None,
+        })
+    }
+
+    /// Pretends it is `date` from now on.
+    pub fn set_date(&mut self, date: NaiveDate) {
+        self.date = Some(date);
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date
+            .unwrap_or_else(|| Utc::now().with_timezone(&self.timezone).date_naive())
+    }
+
+    /// Whether a door opening on `day` of December is open.
+    pub fn is_unlocked(&self, day: usize) -> bool {
+        self.unlock_all || self.days_until(day) <= 0
+    }
+
+    /// How many days are left until `day` of December. Zero or less once it
+    /// has come.
+    pub fn days_until(&self, day: usize) -> i64 {
+        let today = self.today();
+        match NaiveDate::from_ymd_opt(season(today), 12, day as u32) {
+            Some(opens) => (opens - today).num_days(),
+            None => i64::MAX,
+        }
+    }
+}
+
+/// The year of the December the calendar is in on `date`: the same year in
+/// December, and the year before until then.
+fn season(date: NaiveDate) -> i32 {
+    if date.month() == 12 {
+        date.year()
+    } else {
+        date.year() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::CalendarConfig;
+    use super::*;
+
+    fn calendar_on(year: i32, month: u32, day: u32) -> Calendar {
+        let mut calendar = Calendar::new(&CalendarConfig::default()).unwrap();
+        calendar.set_date(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+        calendar
+    }
+
+    #[test]
+    fn doors_open_one_by_one_in_december() {
+        let calendar = calendar_on(2024, 12, 4);
+        assert!(calendar.is_unlocked(4));
+        assert!(!calendar.is_unlocked(5));
+        assert_eq!(calendar.days_until(24), 20);
+    }
+
+    #[test]
+    fn doors_stay_open_after_new_year() {
+        let calendar = calendar_on(2025, 1, 1);
+        assert!(calendar.is_unlocked(24));
+        assert_eq!(calendar.days_until(24), -8);
+        assert!(calendar_on(2025, 11, 30).is_unlocked(24));
+    }
+
+    #[test]
+    fn doors_close_when_the_next_december_comes() {
+        let calendar = calendar_on(2025, 12, 1);
+        assert!(calendar.is_unlocked(1));
+        assert!(!calendar.is_unlocked(2));
+    }
+}
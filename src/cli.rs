@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+use chrono::NaiveDate;
+use clap::Parser;
+
+/// Systeks julekalender, in the terminal.
+///
+/// Settings that are kept between sessions, like key bindings and the time
+/// zone of the calendar, go in the config file instead.
+#[derive(Parser)]
+#[command(name = "julekalender", version)]
+pub struct Args {
+    /// Open door N right away, locked or not, instead of starting at the
+    /// calendar.
+    #[arg(long, value_name = "N")]
+    pub day: Option<usize>,
+
+    /// Seed the random numbers, to get the same session every time.
+    #[arg(long, conflicts_with = "replay")]
+    pub seed: Option<u64>,
+
+    /// The frame rate to aim for. 0 runs as fast as possible.
+    #[arg(long)]
+    pub fps: Option<u32>,
+
+    /// Switch between the calendar and the days without transitions.
+    #[arg(long)]
+    pub no_transitions: bool,
+
+    /// Show the frame rate, the mouse and the keys held.
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Pretend today is DATE, like 2024-12-14, when deciding which doors are
+    /// open.
+    #[arg(long, value_name = "DATE", conflicts_with = "replay")]
+    pub date: Option<NaiveDate>,
+
+    /// Show everything in LANGUAGE, like nb or en, instead of the one in the
+    /// config file.
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Read the quizzes and catalogs from DIR/quizzes and DIR/locales instead of the ones built in.
+    #[arg(long, value_name = "DIR")]
+    pub content: Option<PathBuf>,
+
+    /// Read art from DIR/art, in place of the built in art with the same names.
+    #[arg(long, value_name = "DIR")]
+    pub assets: Option<PathBuf>,
+
+    /// Read the quizzes, catalogs and art again whenever their files change,
+    /// for working on them. Without --content and --assets, the content and
+    /// assets folders in the working directory are used.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Delete all saved progress before starting.
+    #[arg(long, conflicts_with = "replay")]
+    pub reset_progress: bool,
+
+    /// Record the session to FILE.
+    #[arg(long, value_name = "FILE", env = "JULEKALENDER_RECORD")]
+    pub record: Option<PathBuf>,
+
+    /// Play back the recording in FILE instead of reading the terminal.
+    #[arg(long, value_name = "FILE", env = "JULEKALENDER_REPLAY", conflicts_with_all = ["record", "day", "no_transitions", "language", "watch"])]
+    pub replay: Option<PathBuf>,
+}
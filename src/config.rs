@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+use serde::Deserialize;
+use crate::input::DEFAULT_DOUBLE_CLICK_INTERVAL;
+use crate::locale::DEFAULT_LANGUAGE;
+
+const CONFIG_FILE: &str = "config.toml";
+const DEFAULT_TIMEZONE: &str = "Europe/Oslo";
+const DEFAULT_FPS: u32 = 60;
+
+/// The user's settings, read from `config.toml` in the julekalender folder of
+/// the platform's config directory (`~/.config/julekalender` on Linux).
+/// Everything is optional, and a missing file means all defaults.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Action names mapped to the keys and mouse buttons that trigger them,
+    /// like `move_left = ["a", "Left"]`. Replaces the defaults of the listed
+    /// actions only.
+    pub bindings: HashMap<String, Vec<String>>,
+    pub mouse: MouseConfig,
+    pub calendar: CalendarConfig,
+    pub display: DisplayConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MouseConfig {
+    /// The longest time in seconds between the presses of a double click.
+    pub double_click_interval: f64,
+}
+
+impl Default for MouseConfig {
+    fn default() -> Self {
+        MouseConfig {
+            double_click_interval: DEFAULT_DOUBLE_CLICK_INTERVAL,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalendarConfig {
+    /// The time zone the doors open in, like `Europe/Oslo`.
+    pub timezone: String,
+    /// Opens every door regardless of the date, for trying out the days
+    /// before December.
+    pub unlock_all: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        CalendarConfig {
+            timezone: DEFAULT_TIMEZONE.to_string(),
+            unlock_all: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// The frame rate to aim for. 0 runs as fast as possible.
+    pub fps: u32,
+    /// Whether to animate the switch between the calendar and the days.
+    pub transitions: bool,
+    /// The language to show everything in, like `nb` or `en`.
+    pub language: String,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            fps: DEFAULT_FPS,
+            transitions: true,
+            language: DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Config, Error> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+            Err(error) => return Err(error),
+        };
+
+        toml::from_str(&text).map_err(|error| {
+            Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), error))
+        })
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("julekalender").join(CONFIG_FILE))
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use crate::art::{Art, ArtLibrary};
+use crate::locale::Catalog;
+use crate::quiz::{self, Quiz};
+
+/// The files in `content` and `assets/art`, embedded by `build.rs`.
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/content.rs"));
+}
+
+/// Where quizzes, catalogs and other content files are read from. The content is built
+/// into the binary, unless a folder laid out like `content` is given instead.
+pub struct Content {
+    dir: Option<PathBuf>,
+    /// A folder laid out like `assets`, whose art replaces the built in art.
+    assets: Option<PathBuf>,
+}
+
+impl Content {
+    pub fn embedded() -> Content {
+        Content { dir: None, assets: None }
+    }
+
+    pub fn from_dir(dir: PathBuf) -> Content {
+        Content { dir: Some(dir), assets: None }
+    }
+
+    pub fn assets(mut self, dir: PathBuf) -> Content {
+        self.assets = Some(dir);
+        self
+    }
+
+    /// Every quiz, in the order of their file names. The art they use must
+    /// be in `art`.
+    pub fn quizzes(&self, art: &ArtLibrary) -> Result<Vec<Quiz>, Error> {
+        self.files("quizzes", embedded::QUIZZES)?
+            .iter()
+            .map(|(name, text)| quiz::parse(text, art).map_err(|error| invalid(name, error)))
+            .collect()
+    }
+
+    /// The built in art, with the art in the assets folder in place of the
+    /// art of the same name. Art that replaces built in art must keep the
+    /// anchors and hotspots in `REQUIRED_ART`.
+    pub fn art(&self) -> Result<ArtLibrary, Error> {
+        let mut library = ArtLibrary::new();
+
+        let embedded = embedded::ART
+            .iter()
+            .map(|(name, contents)| (name.to_string(), contents.to_vec()))
+            .collect();
+        add_art(&mut library, embedded)?;
+
+        if let Some(dir) = &self.assets {
+            add_art(&mut library, read_files(&dir.join("art"), ART_EXTENSIONS)?)?;
+        }
+
+        check_required_art(&library)?;
+        Ok(library)
+    }
+
+    /// The folders the content is read from, other than the built in content.
+    pub fn dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(dir) = &self.dir {
+            dirs.push(dir.join("quizzes"));
+            dirs.push(dir.join("locales"));
+        }
+        if let Some(dir) = &self.assets {
+            dirs.push(dir.join("art"));
+        }
+        dirs
+    }
+
+    /// The catalog of every language, by the language code the file is
+    /// named after.
+    pub fn catalogs(&self) -> Result<BTreeMap<String, Catalog>, Error> {
+        self.files("locales", embedded::LOCALES)?
+            .iter()
+            .map(|(name, text)| {
+                let language = Path::new(name).file_stem().unwrap_or_default().to_string_lossy().to_string();
+                Ok((language, Catalog::parse(text).map_err(|error| invalid(name, error))?))
+            })
+            .collect()
+    }
+
+    /// The names and contents of the files in the `folder` of the content.
+    fn files(&self, folder: &str, embedded: &[(&str, &[u8])]) -> Result<Vec<(String, String)>, Error> {
+        let files = match &self.dir {
+            Some(dir) => read_files(&dir.join(folder), &["toml"])?,
+            None => embedded
+                .iter()
+                .map(|(name, contents)| (name.to_string(), contents.to_vec()))
+                .collect(),
+        };
+
+        files
+            .into_iter()
+            .map(|(name, contents)| {
+                let text = text(&name, &contents)?.to_string();
+                Ok((name, text))
+            })
+            .collect()
+    }
+}
+
+fn invalid(name: &str, error: String) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("{}: {}", name, error))
+}
+
+/// The files art is made of: the characters in `.txt` files, or `.ans` files
+/// with both characters and colors, the colors of `.txt` files in `.mask`
+/// files, and the metadata in `.toml` files.
+const ART_EXTENSIONS: &[&str] = &["txt", "ans", "mask", "toml"];
+
+/// Adds the art in `files`, where the files of a piece of art are named
+/// after it.
+fn add_art(library: &mut ArtLibrary, files: Vec<(String, Vec<u8>)>) -> Result<(), Error> {
+    let files: BTreeMap<String, Vec<u8>> = files.into_iter().collect();
+    let beside = |path: &Path, extension: &str| files.get(&path.with_extension(extension).to_string_lossy().to_string());
+
+    for (name, contents) in &files {
+        let path = Path::new(name);
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let metadata = beside(path, "toml").map(|metadata| text(name, metadata)).transpose()?;
+
+        let art = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("txt") if beside(path, "ans").is_some() => {
+                return Err(invalid(name, format!("there is also an .ans file for '{}'", stem)));
+            }
+            Some("txt") => {
+                let mask = beside(path, "mask").map(|mask| text(name, mask)).transpose()?;
+                Art::parse(text(name, contents)?, mask, metadata)
+            }
+            Some("ans") => Art::parse_ansi(contents, metadata),
+            Some("mask") if beside(path, "txt").is_none() => {
+                return Err(invalid(name, format!("there is no .txt file for '{}' to color", stem)));
+            }
+            Some("toml") if beside(path, "txt").is_none() && beside(path, "ans").is_none() => {
+                return Err(invalid(name, format!("there is no art named '{}' for this metadata", stem)));
+            }
+            _ => continue,
+        };
+        library.insert(stem, art.map_err(|error| invalid(name, error))?);
+    }
+    Ok(())
+}
+
+/// A named point or area of a piece of art.
+#[derive(Clone, Copy)]
+enum Part {
+    Anchor(&'static str),
+    Hotspot(&'static str),
+}
+
+/// The anchors and hotspots the days look up by name, besides the anchors
+/// every piece of art has.
+const REQUIRED_ART: &[(&str, Part)] = &[
+    ("santa_face", Part::Anchor("peek")),
+    ("norway", Part::Hotspot("hamar")),
+];
+
+fn check_required_art(library: &ArtLibrary) -> Result<(), Error> {
+    for &(name, part) in REQUIRED_ART {
+        let Some(art) = library.get(name) else {
+            return Err(invalid(name, "the art is missing".to_string()));
+        };
+        let (found, kind, part_name) = match part {
+            Part::Anchor(anchor) => (art.anchor(anchor).is_some(), "anchor", anchor),
+            Part::Hotspot(hotspot) => (art.has_hotspot(hotspot), "hotspot", hotspot),
+        };
+        if !found {
+            return Err(invalid(name, format!("the art has no {} '{}', which the calendar uses", kind, part_name)));
+        }
+    }
+    Ok(())
+}
+
+/// `contents` as text, or an error if the file named `name` is not UTF-8.
+fn text<'a>(name: &str, contents: &'a [u8]) -> Result<&'a str, Error> {
+    std::str::from_utf8(contents).map_err(|error| invalid(name, error.to_string()))
+}
+
+/// The names and contents of the files in `dir` with one of the `extensions`,
+/// sorted by name.
+fn read_files(dir: &Path, extensions: &[&str]) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    // Naming the folder, since a missing one is the likely mistake.
+    let entries = fs::read_dir(dir).map_err(|error| Error::new(error.kind(), format!("{}: {}", dir.display(), error)))?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extensions.iter().any(|wanted| extension == *wanted)) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| Ok((path.display().to_string(), fs::read(&path)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Content with `files` in the art folder of its assets.
+    fn assets_with(files: &[(&str, &str)]) -> (Content, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("julekalender-assets-{}-{}", std::process::id(), files[0].0));
+        fs::create_dir_all(dir.join("art")).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join("art").join(name), contents).unwrap();
+        }
+        (Content::embedded().assets(dir.clone()), dir)
+    }
+
+    #[test]
+    fn built_in_art_has_every_required_part() {
+        assert!(Content::embedded().art().is_ok());
+    }
+
+    #[test]
+    fn replaced_art_must_keep_its_anchors() {
+        let (content, dir) = assets_with(&[("santa_face.txt", "(o_o)")]);
+        let error = content.art().err().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(error.to_string(), "santa_face: the art has no anchor 'peek', which the calendar uses");
+    }
+
+    #[test]
+    fn replaced_art_must_keep_its_hotspots() {
+        let (content, dir) = assets_with(&[("norway.txt", "/\\"), ("norway.toml", "[hotspots]\noslo = [0, 0, 1, 1]")]);
+        let error = content.art().err().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(error.to_string(), "norway: the art has no hotspot 'hamar', which the calendar uses");
+    }
+}
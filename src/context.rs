@@ -0,0 +1,105 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::art::ArtLibrary;
+use crate::calendar::Calendar;
+use crate::config::Config;
+use crate::input::Input;
+use crate::locale::Locale;
+use crate::save::Progress;
+use crate::screen::Screen;
+use crate::states::Days;
+use crate::timing::Clock;
+
+/// Everything the states share, handed to every state method. New services
+/// the states need go here, instead of in globals.
+pub struct Context {
+    pub screen: Screen,
+    pub input: Input,
+    /// The only source of randomness for the states. It is seeded once per
+    /// session, so replaying a recording gives the same random numbers.
+    pub rng: StdRng,
+    pub clock: Clock,
+    pub config: Config,
+    pub calendar: Calendar,
+    pub progress: Progress,
+    pub days: Days,
+    pub locale: Locale,
+    pub art: ArtLibrary,
+}
+
+impl Context {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(screen: Screen, input: Input, config: Config, calendar: Calendar, progress: Progress, days: Days, locale: Locale, art: ArtLibrary, seed: u64) -> Context {
+        Context {
+            screen,
+            input,
+            rng: StdRng::seed_from_u64(seed),
+            clock: Clock::new(),
+            config,
+            calendar,
+            progress,
+            days,
+            locale,
+            art,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use chrono::NaiveDate;
+    use crossterm::event::Event;
+    use crate::backend::{HeadlessBackend, HeadlessTerminal, ScriptedEvents};
+    use crate::calendar::Calendar;
+    use crate::config::Config;
+    use crate::content::Content;
+    use crate::input::Input;
+    use crate::locale::Locale;
+    use crate::save::Progress;
+    use crate::screen::Screen;
+    use crate::states::Days;
+    use super::Context;
+
+    /// A context for tests, with the built in content, drawing to a
+    /// `HeadlessBackend` of `size` and reading input from `events`. It is
+    /// Christmas Eve, so every door is open.
+    pub struct Harness {
+        pub ctx: Context,
+        pub terminal: Rc<RefCell<HeadlessTerminal>>,
+        pub events: Rc<RefCell<VecDeque<Event>>>,
+    }
+
+    impl Harness {
+        pub fn new(size: (u16, u16)) -> Harness {
+            let backend = HeadlessBackend::new(size);
+            let terminal = backend.terminal();
+            let mut screen = Screen::new(Box::new(backend), size);
+            screen.init().unwrap();
+
+            let source = ScriptedEvents::new();
+            let events = source.queue();
+            let mut input = Input::with_source(Box::new(source));
+            input.set_key_releases(screen.reports_key_releases());
+
+            let config = Config::default();
+            let mut calendar = Calendar::new(&config.calendar).unwrap();
+            calendar.set_date(NaiveDate::from_ymd_opt(2024, 12, 24).unwrap());
+
+            let content = Content::embedded();
+            let art = content.art().unwrap();
+            let days = Days::new(content.quizzes(&art).unwrap()).unwrap();
+            let locale = Locale::new(content.catalogs().unwrap(), "nb").unwrap();
+
+            let ctx = Context::new(screen, input, config, calendar, Progress::in_memory(), days, locale, art, 0);
+            Harness { ctx, terminal, events }
+        }
+
+        /// Queues `event` to be handled by the next `Input::update`.
+        pub fn send(&self, event: Event) {
+            self.events.borrow_mut().push_back(event);
+        }
+    }
+}
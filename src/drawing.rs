@@ -3,17 +3,27 @@ extern crate crossterm;
 use crossterm::style;
 use crossterm::style::Color;
 use crate::input::{Input, InputEvent, MouseButton};
-use crate::screen::Screen;
+use crate::screen::{Screen, Style};
+use crate::timing::Clock;
+
+const DOOR_HIGHLIGHT: Color = Color::Rgb { r: 150, g: 20, b: 20 };
+const LOCKED_DOOR: Color = Color::DarkGrey;
+const OPENED_DOOR: Color = Color::Cyan;
+const COMPLETED_DOOR: Color = Color::Green;
+/// The warm light behind a door that is being opened.
+const DOOR_LIGHT: Color = Color::Rgb { r: 255, g: 190, b: 80 };
+const ERROR: Color = Color::Rgb { r: 120, g: 0, b: 0 };
 
 pub fn draw_debug_info(
     screen: &mut Screen,
     input: &mut Input,
+    clock: &Clock,
     dt: f64,
 ) {
-    let fps_str = format!("FPS: {:.0}", 1.0 / dt);
+    let fps_str = format!("FPS: {:.0}  Frame: {}  Time: {:.1}s", 1.0 / dt, clock.frame(), clock.time());
     screen.draw_text(0, 0, &fps_str, Color::White);
 
-    let mouse_pos_str = format!("Mouse: ({}, {})", input.mouse_position().0, input.mouse_position().1);
+    let mouse_pos_str = format!("Mouse: ({}, {})  Modifiers: {:?}", input.mouse_position().0, input.mouse_position().1, input.modifiers());
     screen.draw_text(0, 1, &mouse_pos_str, Color::White);
 
     let mouse_buttons = vec![
@@ -26,7 +36,6 @@ pub fn draw_debug_info(
         let mouse_state_str = match input_event {
             Some(InputEvent::Down) => format!("{}: Down", button),
             Some(InputEvent::Up) => format!("{}: Up", button),
-            Some(InputEvent::None) => format!("{}: None", button),
             None => format!("{}: None", button),
         };
         for (j, c) in mouse_state_str.chars().enumerate() {
@@ -34,33 +43,55 @@ pub fn draw_debug_info(
         }
     }
 
+    let drag_str = match input.drag() {
+        Some(drag) => {
+            let phase = if drag.is_started() { "started" } else if drag.is_ended() { "ended" } else { "moving" };
+            format!("Drag {:?} {}: {:?} -> {:?}", drag.button, phase, drag.start, drag.current)
+        }
+        None => "Drag: None".to_string(),
+    };
+    let double_clicked = input.is_mouse_double_clicked(MouseButton::Left);
+    let gesture_str = format!("{}  Moved: {}  Scroll: {:?}  Double click: {}", drag_str, input.mouse_moved(), input.scroll_delta(), double_clicked);
+    screen.draw_text(0, 5, &gesture_str, Color::White);
+
     // draw all keys that are pressed
     let keymap = input.keymap();
-    for (i, (key, input_event)) in keymap.iter().enumerate() {
-        match input_event {
-            Some(InputEvent::Down) => {
-                let key_str = format!("Key {}: Down", key);
-                for (j, c) in key_str.chars().enumerate() {
-                    screen.set_cell(j as u16, (i + 5) as u16, c, style::Color::White);
-                }
-            }
-            Some(InputEvent::Up) => {
-                let key_str = format!("Key {}: Up", key);
-                for (j, c) in key_str.chars().enumerate() {
-                    screen.set_cell(j as u16, (i + 5) as u16, c, style::Color::White);
-                }
-            }
-            Some(InputEvent::None) => {
-                let key_str = format!("Key {}: None", key);
-                for (j, c) in key_str.chars().enumerate() {
-                    screen.set_cell(j as u16, (i + 5) as u16, c, style::Color::White);
-                }
-            }
-            None => {}
+    for (i, (key, state)) in keymap.iter().enumerate() {
+        let key_str = match state.event() {
+            Some(InputEvent::Down) => format!("Key {}: Down", key),
+            Some(InputEvent::Up) => format!("Key {}: Up", key),
+            None => format!("Key {}: None", key),
+        };
+        for (j, c) in key_str.chars().enumerate() {
+            screen.set_cell(j as u16, (i + 6) as u16, c, style::Color::White);
         }
     }
 }
 
+/// Draws `header` and the lines of `error` across the screen, with the last
+/// line just above `bottom`, over everything else. Returns the row of the
+/// header, so another error can be drawn above it.
+pub fn draw_error(screen: &mut Screen, header: &str, error: &str, bottom: u16) -> u16 {
+    let style = Style::new(Color::White).background(ERROR);
+    let lines: Vec<String> = std::iter::once(header)
+        .chain(error.lines())
+        .map(|line| format!(" {}", line))
+        .collect();
+
+    let top = bottom.saturating_sub(lines.len() as u16);
+    for (i, line) in lines.iter().enumerate() {
+        let y = top + i as u16;
+        if y >= bottom.min(screen.height()) {
+            break;
+        }
+        let mut chars = line.chars();
+        for x in 0..screen.width() {
+            screen.set_cell(x, y, chars.next().unwrap_or(' '), style);
+        }
+    }
+    top
+}
+
 pub fn draw_ground(screen: &mut Screen) {
     for i in 0..screen.width() {
         screen.set_cell(i, screen.height() - 1, '█', style::Color::White);
@@ -84,104 +115,79 @@ pub fn draw_ascii_safe(screen: &mut Screen, ascii: &str, x: i16, y: i16) {
     draw_ascii_safe_c(screen, ascii, x, y, style::Color::White);
 }
 
-pub fn draw_ascii(screen: &mut Screen, ascii: &str, x: u16, y: u16) {
-    draw_ascii_safe(screen, ascii, x as i16, y as i16);
-}
-
+/// Draws `question` with its `answers` side by side below it, and returns the
+/// answer that was clicked, if any.
 pub fn draw_question(screen: &mut Screen, mouse_position: (u16, u16), mouse_down: bool,
     question: &str,
-    correct_answer: &str,
-    wrong_answers: &[&str],
-    correct_answer_position: usize,
-    on_correct_answer: &mut dyn FnMut(),
-) {
+    answers: &[&str],
+) -> Option<usize> {
     let width = screen.width();
     let height = screen.height();
 
     draw_text_box(screen, width, height, question, 0, -5, (0, 0), false);
 
-    let total_answers = wrong_answers.len() + 1;
-
-    let mut incorrect_is_hovered: Vec<bool> = vec![];
-    let mut correct_answer_hovered: bool = false;
-
-
     let delta_offset: i16 = 20;
     let minimum_offset: i16 =
-        (((total_answers - 1) as f32 / 2.0) * -delta_offset as f32) as i16;
+        (((answers.len() as i16 - 1) as f32 / 2.0) * -delta_offset as f32) as i16;
 
-    let mut wrong_answer_iterator: usize = 0;
-
-    for i in 0..total_answers {
+    let mut clicked = None;
+    for (i, answer) in answers.iter().enumerate() {
         let x_offset = minimum_offset + delta_offset * i as i16;
-        if i == correct_answer_position {
-            correct_answer_hovered = draw_text_box(
-                screen,
-                width,
-                height,
-                correct_answer,
-                x_offset,
-                0,
-                mouse_position,
-                mouse_down,
-            );
-        } else {
-            incorrect_is_hovered.push(draw_text_box(
-                screen,
-                width,
-                height,
-                wrong_answers[wrong_answer_iterator],
-                x_offset,
-                0,
-                mouse_position,
-                mouse_down,
-            ));
-            wrong_answer_iterator += 1;
-        }
-    }
-
-    if mouse_down {
-        let any_incorrect_is_hovered = incorrect_is_hovered.into_iter().any(|x| x);
-        if correct_answer_hovered && !any_incorrect_is_hovered {
-            draw_text_box(
-                screen,
-                width,
-                height,
-                "Correct!",
-                0,
-                5,
-                (0, 0),
-                false,
-            );
-            on_correct_answer();
-        } else if any_incorrect_is_hovered {
-            draw_text_box(screen, width, height, "Wrong!", 0, 5, (0, 0), false);
+        let hovered = draw_text_box(screen, width, height, answer, x_offset, 0, mouse_position, mouse_down);
+        if hovered && mouse_down {
+            clicked = Some(i);
         }
     }
+    clicked
 }
 
 pub fn draw_text_box(screen: &mut Screen, width: u16, height: u16, q: &str, x_offset: i16, y_offset: i16, mouse_position: (u16, u16), mouse_down: bool) -> bool {
-    let question = q;
-    let len = question.len() as i16;
-    let w = width as i16;
-    let h = height as i16;
-    let x_origin = (w - len) / 2 + x_offset;
-    let y_origin = h / 2 + y_offset;
+    let origin = text_box_origin(width, height, q, x_offset, y_offset);
+    draw_box(screen, origin, height as i16, q, mouse_position, mouse_down, Style::default())
+}
 
-    let fancy_top_border = "╭".to_string() + &"─".repeat(question.len() + 4) + "╮";
-    let fancy_bottom_border = "╰".to_string() + &"─".repeat(question.len() + 4) + "╯";
+/// Same as `draw_text_box`, but the inside of the box and the text are drawn
+/// with `fill`, which allows for background colors and text attributes.
+pub fn draw_text_box_styled(screen: &mut Screen, q: &str, x_offset: i16, y_offset: i16, mouse_position: (u16, u16), mouse_down: bool, fill: Style) -> bool {
+    let origin = text_box_origin(screen.width(), screen.height(), q, x_offset, y_offset);
+    let height = screen.height() as i16;
+    draw_box(screen, origin, height, q, mouse_position, mouse_down, fill)
+}
 
-    let mut color = style::Color::White;
+pub fn text_box_hovered(width: u16, height: u16, q: &str, x_offset: i16, y_offset: i16, mouse_position: (u16, u16)) -> bool {
+    let (x_origin, y_origin) = text_box_origin(width, height, q, x_offset, y_offset);
+    is_box_hovered(x_origin, y_origin, q.len() as i16, mouse_position)
+}
+
+fn text_box_origin(width: u16, height: u16, q: &str, x_offset: i16, y_offset: i16) -> (i16, i16) {
+    let len = q.len() as i16;
+    let w = width as i16;
+    let h = height as i16;
+    ((w - len) / 2 + x_offset, h / 2 + y_offset)
+}
 
+fn is_box_hovered(x_origin: i16, y_origin: i16, len: i16, mouse_position: (u16, u16)) -> bool {
     let mx = mouse_position.0 as i16;
     let my = mouse_position.1 as i16;
 
-    let mut is_hovered = false;
-    if mx >= x_origin - 3 &&
+    mx >= x_origin - 3 &&
         mx <= x_origin + len + 2 &&
         my >= y_origin - 1 &&
-        my <= y_origin + 1 {
+        my <= y_origin + 1
+}
 
+fn draw_box(screen: &mut Screen, (x_origin, y_origin): (i16, i16), h: i16, q: &str, mouse_position: (u16, u16), mouse_down: bool, fill: Style) -> bool {
+    let question = q;
+    let len = question.len() as i16;
+    let w = screen.width() as i16;
+
+    let fancy_top_border = "╭".to_string() + &"─".repeat(question.len() + 4) + "╮";
+    let fancy_bottom_border = "╰".to_string() + &"─".repeat(question.len() + 4) + "╯";
+
+    let mut color = fill.foreground;
+
+    let is_hovered = is_box_hovered(x_origin, y_origin, len, mouse_position);
+    if is_hovered {
         if mouse_down {
             color = style::Color::Rgb {
                 r: 0,
@@ -195,15 +201,17 @@ pub fn draw_text_box(screen: &mut Screen, width: u16, height: u16, q: &str, x_of
                 b: 0,
             };
         }
-        is_hovered = true;
     }
 
+    let border = Style::new(color).background(fill.background);
+    let text = Style { foreground: color, ..fill };
+
     for i in 0..3 {
         let line = " ".repeat(question.len() + 4);
         for (j, c) in line.chars().enumerate() {
             let x_offset = x_origin - 2 + j as i16;
             let y_offset = y_origin - 1 + i as i16;
-            screen.set_cell_safe(x_offset, y_offset, c, style::Color::White);
+            screen.set_cell_safe(x_offset, y_offset, c, fill);
         }
     }
 
@@ -212,7 +220,7 @@ pub fn draw_text_box(screen: &mut Screen, width: u16, height: u16, q: &str, x_of
         for (j, c) in line.chars().enumerate() {
             let x_offset = x_origin - 3 + j as i16;
             let y_offset = y_origin - 1i16;
-            screen.set_cell_safe(x_offset, y_offset, c, color);
+            screen.set_cell_safe(x_offset, y_offset, c, border);
         }
     }
 
@@ -222,79 +230,159 @@ pub fn draw_text_box(screen: &mut Screen, width: u16, height: u16, q: &str, x_of
         for (j, c) in line.chars().enumerate() {
             let x_offset = x_origin - 3 + j as i16;
             let y_offset = y_origin + 1i16;
-            screen.set_cell_safe(x_offset, y_offset, c, color);
+            screen.set_cell_safe(x_offset, y_offset, c, border);
         }
     }
 
     if x_origin > 3 {
         let x_offset = x_origin - 3i16;
         let y_offset = y_origin;
-        screen.set_cell_safe(x_offset, y_offset, '│', color);
+        screen.set_cell_safe(x_offset, y_offset, '│', border);
     }
 
     if x_origin + len + 2 < w {
         let x_offset = x_origin + len + 2i16;
         let y_offset = y_origin;
-        screen.set_cell_safe(x_offset, y_offset, '│', color);
+        screen.set_cell_safe(x_offset, y_offset, '│', border);
     }
 
     for (i, c) in question.chars().enumerate() {
         let x_offset = x_origin + i as i16;
         let y_offset = y_origin;
-        screen.set_cell_safe(x_offset, y_offset, c, color);
+        screen.set_cell_safe(x_offset, y_offset, c, text);
     }
 
     is_hovered
 }
 
-pub fn draw_calendar(
-    screen: &mut Screen,
-    mouse_position: (u16, u16),
-    mouse_down: bool,
-) -> Option<usize> {
-    let total_days:usize = 24;
-    let columns:i16 = 6;
-    let rows:i16 = (total_days as f32 / columns as f32).ceil() as i16;
+/// How a door of the calendar is drawn.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Door {
+    /// There is no day behind the door yet.
+    Empty,
+    /// Its day has not come yet.
+    Locked,
+    /// Never opened.
+    Available,
+    /// Opened, but not completed.
+    Opened,
+    Completed,
+}
+
+pub const CALENDAR_DOORS: usize = 24;
+const CALENDAR_COLUMNS: i16 = 6;
+
+/// Where `door` is drawn, as offsets from the middle of the screen like
+/// `draw_text_box` takes.
+fn calendar_door_offset(door: usize) -> (i16, i16) {
+    let rows:i16 = (CALENDAR_DOORS as f32 / CALENDAR_COLUMNS as f32).ceil() as i16;
 
     let box_width = 6;
 
     let padding = 4;
-    let x_start:i16 = -(columns * box_width) / 2 - box_width;
+    let x_start:i16 = -(CALENDAR_COLUMNS * box_width) / 2 - box_width;
     let y_start:i16 = -((rows - 1) * padding) / 2;
 
     let x_step = box_width + padding;
     let y_step = padding;
 
-    let mut day_is_hovered = vec![false; total_days];
+    let i = door as i16 - 1;
+    (x_start + (i % CALENDAR_COLUMNS) * x_step, y_start + (i / CALENDAR_COLUMNS) * y_step)
+}
+
+fn door_label(door: usize) -> String {
+    format!("{:02}", door)
+}
+
+/// Draws the doors of the calendar, and returns the door under the mouse.
+/// Empty doors are never returned.
+pub fn draw_calendar(
+    screen: &mut Screen,
+    mouse_position: (u16, u16),
+    mouse_down: bool,
+    door: impl Fn(usize) -> Door,
+) -> Option<usize> {
+    let mut day_is_hovered = [false; CALENDAR_DOORS];
+
+    for i in 0..CALENDAR_DOORS {
+        let (x_offset, y_offset) = calendar_door_offset(i + 1);
 
-    for i in 0..total_days {
-        let x_offset = x_start + (i as i16 % columns) * x_step;
-        let y_offset = y_start + (i as i16 / columns) * y_step;
+        let door = door(i + 1);
+        let day_text = if door == Door::Empty {
+            "  ".to_string()
+        } else {
+            door_label(i + 1)
+        };
+
+        // Empty doors are drawn away from the mouse, so they never light up.
+        let mouse_position = if door == Door::Empty { (u16::MAX, u16::MAX) } else { mouse_position };
 
-        let day_text = if i < 9 {
-            format!("0{}", i + 1)
+        let hovered = text_box_hovered(screen.width(), screen.height(), &day_text, x_offset, y_offset, mouse_position);
+        let fill = match door {
+            Door::Empty | Door::Locked => Style::new(LOCKED_DOOR),
+            Door::Available => Style::default(),
+            Door::Opened => Style::new(OPENED_DOOR),
+            Door::Completed => Style::new(COMPLETED_DOOR),
+        };
+        let fill = if hovered && door != Door::Empty && door != Door::Locked {
+            fill.background(DOOR_HIGHLIGHT).bold()
         } else {
-            (i + 1).to_string()
+            fill
         };
 
-        day_is_hovered[i] = draw_text_box(
+        day_is_hovered[i] = draw_text_box_styled(
             screen,
-            screen.width(),
-            screen.height(),
             &day_text,
             x_offset,
             y_offset,
             mouse_position,
             mouse_down,
+            fill,
         );
+
+        let (x, y) = text_box_origin(screen.width(), screen.height(), &day_text, x_offset, y_offset);
+        match door {
+            // The shackle of a padlock on top of the box.
+            Door::Locked => {
+                screen.set_cell_safe(x, y - 2, '╭', LOCKED_DOOR);
+                screen.set_cell_safe(x + 1, y - 2, '╮', LOCKED_DOOR);
+            }
+            Door::Completed => screen.set_cell_safe(x + day_text.len() as i16 + 2, y - 1, '★', Color::Yellow),
+            _ => {}
+        }
     }
 
-    if mouse_down {
-        for (i, is_hovered) in day_is_hovered.iter().enumerate() {
-            if *is_hovered {
-                return Some(i+1);
+    day_is_hovered.iter().position(|hovered| *hovered).map(|i| i + 1)
+}
+
+/// Draws `door` swinging open on its left hinge, `progress` of the way from
+/// closed to open.
+pub fn draw_door_opening(screen: &mut Screen, door: usize, progress: f64) {
+    let (x_offset, y_offset) = calendar_door_offset(door);
+    let label = door_label(door);
+    let (x_origin, y_origin) = text_box_origin(screen.width(), screen.height(), &label, x_offset, y_offset);
+
+    let left = x_origin - 3;
+    let width = label.len() as i16 + 6;
+
+    // Seen from the front, the door gets narrower as it turns towards us.
+    let angle = progress.clamp(0.0, 1.0) * std::f64::consts::FRAC_PI_2;
+    let door_width = (width as f64 * angle.cos()).round() as i16;
+
+    let light = Style::new(Color::Yellow).background(DOOR_LIGHT);
+    let panel = Style::new(DOOR_HIGHLIGHT).background(DOOR_HIGHLIGHT);
+    let edge = Style::new(Color::White).background(DOOR_HIGHLIGHT);
+
+    for y in y_origin - 1..=y_origin + 1 {
+        for x in left..left + width {
+            let column = x - left;
+            if column < door_width - 1 {
+                screen.set_cell_safe(x, y, ' ', panel);
+            } else if column == door_width - 1 {
+                screen.set_cell_safe(x, y, '▐', edge);
+            } else {
+                screen.set_cell_safe(x, y, ' ', light);
             }
         }
     }
-    None
 }
@@ -1,184 +1,981 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::io::Error;
+use std::str::FromStr;
 use std::time::Duration;
 use crossterm::event;
-use crossterm::event::{read, Event};
+use crossterm::event::Event;
+use crate::actions::{Action, ActionMap};
+use crate::backend::{CrosstermEvents, EventSource};
+use crate::replay::Recorder;
+
+/// Upper bound on how many events are handled in one frame, so a flood of
+/// events can never stall the main loop.
+const MAX_EVENTS_PER_FRAME: usize = 1024;
+
+/// Without key releases, held keys are detected from the terminal's key
+/// repeat. This is the longest expected delay before a held key starts to
+/// repeat...
+const REPEAT_DELAY: f64 = 0.7;
+/// ...and this the longest expected time between two repeats. Presses closer
+/// together than this are too fast to be made by hand.
+const REPEAT_TIMEOUT: f64 = 0.1;
+/// How far the first repeat of a held key may be from the terminal's repeat
+/// delay, once that is known, to count as a repeat and not a second press.
+const REPEAT_DELAY_TOLERANCE: f64 = 0.05;
+
+/// The longest time between the presses of a double click, unless configured
+/// otherwise.
+pub const DEFAULT_DOUBLE_CLICK_INTERVAL: f64 = 0.4;
 
 #[derive(Clone)]
 pub enum InputEvent {
     Down,
     Up,
-    None,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum MouseButton {
     Left,
     Right,
     Middle,
 }
 
+pub type Modifiers = event::KeyModifiers;
+
+const MODIFIER_NAMES: [(Modifiers, &str); 4] = [
+    (Modifiers::CONTROL, "Ctrl"),
+    (Modifiers::ALT, "Alt"),
+    (Modifiers::SHIFT, "Shift"),
+    (Modifiers::SUPER, "Super"),
+];
+
+/// Parses modifier names separated by `+`, like `Ctrl+Shift`.
+pub fn parse_modifiers(names: &str) -> Result<Modifiers, String> {
+    let mut modifiers = Modifiers::NONE;
+    for name in names.split('+').filter(|name| !name.is_empty()) {
+        modifiers |= match name.to_lowercase().as_str() {
+            "ctrl" | "control" => Modifiers::CONTROL,
+            "alt" => Modifiers::ALT,
+            "shift" => Modifiers::SHIFT,
+            "super" => Modifiers::SUPER,
+            _ => return Err(format!("unknown modifier '{}'", name)),
+        };
+    }
+    Ok(modifiers)
+}
+
+/// The names of the modifiers, separated by `+`. Modifiers without a name are
+/// left out.
+pub fn format_modifiers(modifiers: Modifiers) -> String {
+    MODIFIER_NAMES
+        .iter()
+        .filter(|(modifier, _)| modifiers.contains(*modifier))
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// A key on the keyboard, regardless of which modifiers are held with it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Tab,
+    BackTab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+}
+
+impl Key {
+    pub fn from_crossterm(code: event::KeyCode) -> Option<Key> {
+        let key = match code {
+            event::KeyCode::Char(c) => Key::Char(c),
+            event::KeyCode::Up => Key::Up,
+            event::KeyCode::Down => Key::Down,
+            event::KeyCode::Left => Key::Left,
+            event::KeyCode::Right => Key::Right,
+            event::KeyCode::Enter => Key::Enter,
+            event::KeyCode::Esc => Key::Escape,
+            event::KeyCode::Tab => Key::Tab,
+            event::KeyCode::BackTab => Key::BackTab,
+            event::KeyCode::Backspace => Key::Backspace,
+            event::KeyCode::Delete => Key::Delete,
+            event::KeyCode::Insert => Key::Insert,
+            event::KeyCode::Home => Key::Home,
+            event::KeyCode::End => Key::End,
+            event::KeyCode::PageUp => Key::PageUp,
+            event::KeyCode::PageDown => Key::PageDown,
+            event::KeyCode::F(n) => Key::F(n),
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    pub fn to_crossterm(self) -> event::KeyCode {
+        match self {
+            Key::Char(c) => event::KeyCode::Char(c),
+            Key::Up => event::KeyCode::Up,
+            Key::Down => event::KeyCode::Down,
+            Key::Left => event::KeyCode::Left,
+            Key::Right => event::KeyCode::Right,
+            Key::Enter => event::KeyCode::Enter,
+            Key::Escape => event::KeyCode::Esc,
+            Key::Tab => event::KeyCode::Tab,
+            Key::BackTab => event::KeyCode::BackTab,
+            Key::Backspace => event::KeyCode::Backspace,
+            Key::Delete => event::KeyCode::Delete,
+            Key::Insert => event::KeyCode::Insert,
+            Key::Home => event::KeyCode::Home,
+            Key::End => event::KeyCode::End,
+            Key::PageUp => event::KeyCode::PageUp,
+            Key::PageDown => event::KeyCode::PageDown,
+            Key::F(n) => event::KeyCode::F(n),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+
+        let key = match s.to_lowercase().as_str() {
+            "space" => Key::Char(' '),
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "enter" | "return" => Key::Enter,
+            "esc" | "escape" => Key::Escape,
+            "tab" => Key::Tab,
+            "backtab" => Key::BackTab,
+            "backspace" => Key::Backspace,
+            "delete" | "del" => Key::Delete,
+            "insert" | "ins" => Key::Insert,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            name => {
+                let n = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok());
+                match n {
+                    Some(n) if (1..=24).contains(&n) => Key::F(n),
+                    _ => return Err(format!("unknown key '{}'", s)),
+                }
+            }
+        };
+        Ok(key)
+    }
+}
+
+impl From<char> for Key {
+    fn from(c: char) -> Self {
+        Key::Char(c)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(' ') => write!(f, "Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::F(n) => write!(f, "F{}", n),
+            Key::Escape => write!(f, "Esc"),
+            key => write!(f, "{:?}", key),
+        }
+    }
+}
+
+/// A key together with the exact modifiers that have to be held with it, like
+/// Ctrl+C.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Chord {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl Chord {
+    pub fn new(key: impl Into<Key>, modifiers: Modifiers) -> Chord {
+        Chord {
+            key: key.into(),
+            modifiers,
+        }
+    }
+
+    pub fn ctrl(key: impl Into<Key>) -> Chord {
+        Chord::new(key, Modifiers::CONTROL)
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    /// Parses chords like `Ctrl+c` or `Alt+Shift+Left`. The key always comes
+    /// last, so `Ctrl++` is Ctrl and the plus key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (modifier_names, key) = match s.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None if s == "+" => ("", s),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+
+        let modifiers = parse_modifiers(modifier_names).map_err(|error| format!("{} in '{}'", error, s))?;
+        Ok(Chord::new(key.parse::<Key>()?, modifiers))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = format_modifiers(self.modifiers);
+        if !modifiers.is_empty() {
+            write!(f, "{}+", modifiers)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// The state of a key or mouse button during the current frame. Presses and
+/// releases are counted, so a button that goes both down and up between two
+/// frames is reported as both.
+#[derive(Clone, Copy)]
+pub struct ButtonState {
+    held: bool,
+    presses: u32,
+    releases: u32,
+    double_clicks: u32,
+    modifiers: Modifiers,
+}
+
+impl Default for ButtonState {
+    fn default() -> Self {
+        ButtonState {
+            held: false,
+            presses: 0,
+            releases: 0,
+            double_clicks: 0,
+            modifiers: Modifiers::NONE,
+        }
+    }
+}
+
+impl ButtonState {
+    pub fn is_down(&self) -> bool {
+        self.held || self.presses > 0
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.releases > 0
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.presses > 0
+    }
+
+    /// Whether the button went down this frame as the second press of a
+    /// double click. Only mouse buttons are double clicked.
+    pub fn is_double_clicked(&self) -> bool {
+        self.double_clicks > 0
+    }
+
+    /// The modifiers that were held when the button was last pressed.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn event(&self) -> Option<InputEvent> {
+        if self.is_up() {
+            Some(InputEvent::Up)
+        } else if self.is_down() {
+            Some(InputEvent::Down)
+        } else {
+            None
+        }
+    }
+
+    fn press(&mut self, modifiers: Modifiers) {
+        self.held = true;
+        self.presses += 1;
+        self.modifiers = modifiers;
+    }
+
+    fn release(&mut self) {
+        self.held = false;
+        self.releases += 1;
+    }
+
+    fn hold(&mut self) {
+        self.held = true;
+    }
+
+    fn next_frame(&mut self) {
+        self.presses = 0;
+        self.releases = 0;
+        self.double_clicks = 0;
+    }
+
+    fn is_idle(&self) -> bool {
+        !self.held && self.presses == 0 && self.releases == 0
+    }
+}
+
+/// A mouse button being dragged, from where it was pressed to where the
+/// mouse is now.
+#[derive(Clone, Copy, Debug)]
+pub struct Drag {
+    pub button: MouseButton,
+    pub start: (u16, u16),
+    pub current: (u16, u16),
+    moved: bool,
+    started: bool,
+    ended: bool,
+}
+
+impl Drag {
+    /// Whether the mouse started moving with the button held this frame.
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// Whether the button was released this frame, dropping whatever was
+    /// dragged at `current`.
+    pub fn is_ended(&self) -> bool {
+        self.ended
+    }
+}
+
+/// When and where a mouse button was last pressed, for detecting double
+/// clicks.
+#[derive(Clone, Copy)]
+struct Click {
+    button: event::MouseButton,
+    time: f64,
+    position: (u16, u16),
+}
+
+/// When a key was last pressed, for guessing whether it is held on terminals
+/// that do not report key releases.
+#[derive(Clone, Copy)]
+struct KeyRepeat {
+    /// When the key was last pressed or repeated.
+    last_press: f64,
+    /// The time from the press or repeat before that one to `last_press`.
+    gap: f64,
+    /// Whether the last press was a repeat of a held key.
+    repeating: bool,
+}
+
 pub struct Input {
-    keymap: HashMap<event::KeyCode, Option<InputEvent>>,
+    source: Box<dyn EventSource>,
+    keymap: HashMap<Key, ButtonState>,
+    key_releases: bool,
+    repeats: HashMap<Key, KeyRepeat>,
+    /// The delay before the terminal starts repeating a held key, once a key
+    /// has been seen repeating.
+    repeat_delay: Option<f64>,
+    modifiers: Modifiers,
+    time: f64,
     resize: Option<(u16, u16)>,
+    focus_lost: bool,
+    focus_gained: bool,
 
     mouse_position: (u16, u16),
-    mousemap: HashMap<event::MouseButton, Option<InputEvent>>,
+    mouse_moved: bool,
+    mousemap: HashMap<event::MouseButton, ButtonState>,
+    drag: Option<Drag>,
+    scroll: (i32, i32),
+    last_click: Option<Click>,
+    double_click_interval: f64,
+
+    actions: ActionMap,
+    recorder: Option<Recorder>,
 }
 
 impl Input {
     pub fn new() -> Input {
+        Input::with_source(Box::new(CrosstermEvents))
+    }
+
+    pub fn with_source(source: Box<dyn EventSource>) -> Input {
         Input {
+            source,
             keymap: HashMap::new(),
+            key_releases: false,
+            repeats: HashMap::new(),
+            repeat_delay: None,
+            modifiers: Modifiers::NONE,
+            time: 0.0,
             resize: None,
+            focus_lost: false,
+            focus_gained: false,
             mouse_position: (0, 0),
+            mouse_moved: false,
             mousemap: HashMap::new(),
+            drag: None,
+            scroll: (0, 0),
+            last_click: None,
+            double_click_interval: DEFAULT_DOUBLE_CLICK_INTERVAL,
+            actions: ActionMap::new(),
+            recorder: None,
         }
     }
     
-    pub fn keymap(&self) -> &HashMap<event::KeyCode, Option<InputEvent>> {
+    pub fn keymap(&self) -> &HashMap<Key, ButtonState> {
         &self.keymap
     }
     
-    pub fn mousemap(&self) -> &HashMap<event::MouseButton, Option<InputEvent>> {
-        &self.mousemap
-    }
-
     pub fn mouse_position(&self) -> (u16, u16) {
         self.mouse_position
     }
-    
-    pub fn is_key_down(&self, key: char) -> bool {
-        let key = event::KeyCode::Char(key);
-        if let Some(event) = self.keymap.get(&key) {
-            if let Some(InputEvent::Down) = event {
-                return true;
-            }
-        }
 
-        false
+    /// Whether the mouse moved to another cell this frame, with or without a
+    /// button held.
+    pub fn mouse_moved(&self) -> bool {
+        self.mouse_moved
+    }
+
+    /// How far the wheel scrolled this frame, summed over all wheel events.
+    /// Positive y is down and positive x is right.
+    pub fn scroll_delta(&self) -> (i32, i32) {
+        self.scroll
+    }
+
+    /// The drag in progress, or the one that ended this frame. A press only
+    /// becomes a drag once the mouse moves with the button held, so a click
+    /// never shows up here.
+    pub fn drag(&self) -> Option<&Drag> {
+        self.drag.as_ref().filter(|drag| drag.moved)
+    }
+
+    /// The longest time in seconds between two presses of a button for them
+    /// to count as a double click.
+    pub fn set_double_click_interval(&mut self, interval: f64) {
+        self.double_click_interval = interval;
     }
     
-    pub fn is_key_up(&self, key: char) -> bool {
-        let key = event::KeyCode::Char(key);
-        if let Some(event) = self.keymap.get(&key) {
-            if let Some(InputEvent::Up) = event {
-                return true;
-            }
-        }
+    /// Whether the event source reports key releases. If it does not, keys are
+    /// considered held for as long as the terminal keeps repeating them.
+    pub fn set_key_releases(&mut self, key_releases: bool) {
+        self.key_releases = key_releases;
+        self.repeats.clear();
+    }
 
-        false
+    /// The modifiers reported with the most recent key or mouse event.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
     }
 
-    pub fn mouse_state(&self, button: MouseButton) -> Option<InputEvent> {
-        let button = match button {
-            MouseButton::Left => event::MouseButton::Left,
-            MouseButton::Right => event::MouseButton::Right,
-            MouseButton::Middle => event::MouseButton::Middle,
-        };
+    /// Whether the key went down this frame.
+    pub fn is_key_pressed(&self, key: impl Into<Key>) -> bool {
+        self.key_state(key).is_some_and(ButtonState::is_pressed)
+    }
+
+    /// Whether the key is currently held, regardless of when it was pressed.
+    pub fn is_key_held(&self, key: impl Into<Key>) -> bool {
+        self.key_state(key).is_some_and(ButtonState::is_held)
+    }
+
+    /// Whether the chord's key went down this frame with exactly the chord's
+    /// modifiers held.
+    pub fn is_chord_pressed(&self, chord: Chord) -> bool {
+        self.key_state(chord.key)
+            .is_some_and(|state| state.is_pressed() && state.modifiers() == chord.modifiers)
+    }
+
+    /// Whether the chord's key is held, and was last pressed with exactly the
+    /// chord's modifiers.
+    pub fn is_chord_held(&self, chord: Chord) -> bool {
+        self.key_state(chord.key)
+            .is_some_and(|state| state.is_held() && state.modifiers() == chord.modifiers)
+    }
 
-        self.mousemap.get(&button).cloned().unwrap_or(None)
+    fn key_state(&self, key: impl Into<Key>) -> Option<&ButtonState> {
+        self.keymap.get(&key.into())
+    }
+
+    pub fn mouse_state(&self, button: MouseButton) -> Option<InputEvent> {
+        let button = Input::to_crossterm_button(button);
+        self.mousemap.get(&button).and_then(ButtonState::event)
     }
 
     pub fn is_mouse_down(&self, button: MouseButton) -> bool {
-        let button = match button {
-            MouseButton::Left => event::MouseButton::Left,
-            MouseButton::Right => event::MouseButton::Right,
-            MouseButton::Middle => event::MouseButton::Middle,
-        };
-        
-        if let Some(event) = self.mousemap.get(&button) {
-            if let Some(InputEvent::Down) = event {
-                return true;
-            }
-        }
-        
-        false
+        let button = Input::to_crossterm_button(button);
+        self.mousemap.get(&button).is_some_and(ButtonState::is_down)
+    }
+
+    /// Whether the button went down this frame.
+    pub fn is_mouse_pressed(&self, button: MouseButton) -> bool {
+        let button = Input::to_crossterm_button(button);
+        self.mousemap.get(&button).is_some_and(ButtonState::is_pressed)
     }
 
     pub fn is_mouse_up(&self, button: MouseButton) -> bool {
-        let button = match button {
-            MouseButton::Left => event::MouseButton::Left,
-            MouseButton::Right => event::MouseButton::Right,
-            MouseButton::Middle => event::MouseButton::Middle,
-        };
-        
-        if let Some(event) = self.mousemap.get(&button) {
-            if let Some(InputEvent::Up) = event {
-                return true;
-            }
+        let button = Input::to_crossterm_button(button);
+        self.mousemap.get(&button).is_some_and(ButtonState::is_up)
+    }
+
+    /// Whether the button was pressed this frame for the second time within
+    /// the double click interval, at the same position.
+    pub fn is_mouse_double_clicked(&self, button: MouseButton) -> bool {
+        let button = Input::to_crossterm_button(button);
+        self.mousemap.get(&button).is_some_and(ButtonState::is_double_clicked)
+    }
+
+    pub fn actions(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    pub fn set_actions(&mut self, actions: ActionMap) {
+        self.actions = actions;
+    }
+
+    /// Whether any of the action's bindings went down this frame.
+    pub fn is_action_pressed(&self, action: Action) -> bool {
+        self.actions.is_pressed(self, action)
+    }
+
+    /// Whether any of the action's bindings is currently held.
+    pub fn is_action_held(&self, action: Action) -> bool {
+        self.actions.is_held(self, action)
+    }
+
+    /// Writes every frame from now on to `recorder`, to be played back later.
+    pub fn record(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Stops recording, and makes sure everything recorded is written.
+    pub fn finish_recording(&mut self) -> Result<(), Error> {
+        match self.recorder.take() {
+            Some(mut recorder) => recorder.flush(),
+            None => Ok(()),
         }
-        
-        false
     }
 
     pub fn resized(&self) -> Option<(u16, u16)> {
         self.resize
     }
 
-    pub fn update(&mut self) -> Result<(), Error> {
-        for (_, event) in self.mousemap.iter_mut() {
-            if let Some(InputEvent::Up) = event {
-                *event = None;
-            }
+    /// Whether the terminal went to the background this frame.
+    pub fn focus_lost(&self) -> bool {
+        self.focus_lost
+    }
+
+    /// Whether the terminal came back to the foreground this frame.
+    pub fn focus_gained(&self) -> bool {
+        self.focus_gained
+    }
+
+    /// Handles every event that arrived since the previous frame, which lasted
+    /// `dt` seconds.
+    pub fn update(&mut self, dt: f64) -> Result<(), Error> {
+        self.time += dt;
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.frame(dt)?;
         }
 
-        for (_, event) in self.mousemap.iter_mut() {
-            if let Some(InputEvent::Up) = event {
-                *event = Some(InputEvent::None);
-            }
+        for state in self.mousemap.values_mut() {
+            state.next_frame();
         }
-        
-        self.keymap.retain(|_, event| {
-            if let Some(InputEvent::Up) = event {
-                false
-            } else {
-                true
-            }
-        });
 
-        for (_, event) in self.keymap.iter_mut() {
-            if let Some(InputEvent::Down) = event {
-                *event = Some(InputEvent::Up);
-            }
+        self.keymap.retain(|_, state| !state.is_idle());
+
+        for state in self.keymap.values_mut() {
+            state.next_frame();
+        }
+
+        if !self.key_releases {
+            self.release_unrepeated_keys();
         }
 
         self.resize = None;
-        
-        if event::poll(Duration::from_millis(0))? {
-            let raw = read();
+        self.focus_lost = false;
+        self.focus_gained = false;
+        self.mouse_moved = false;
+        self.scroll = (0, 0);
+
+        match &mut self.drag {
+            Some(drag) if drag.ended => self.drag = None,
+            Some(drag) => drag.started = false,
+            None => {}
+        }
 
-            if raw.is_err() {
-                return Err(raw.err().unwrap());
+        let mut handled = 0;
+        while handled < MAX_EVENTS_PER_FRAME && self.source.poll(Duration::ZERO)? {
+            let event = self.source.read()?;
+            if let Some(recorder) = &mut self.recorder {
+                recorder.event(&event)?;
             }
+            self.handle_event(event);
+            handled += 1;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Key(event) => {
+                self.modifiers = event.modifiers;
 
-            let event = raw?;
+                let Some(key) = Key::from_crossterm(event.code) else {
+                    return;
+                };
 
-            if let Event::Key(event) = event {
-                if event.kind == event::KeyEventKind::Press {
-                    self.keymap.insert(event.code, Some(InputEvent::Down));
+                if self.key_releases {
+                    let state = self.keymap.entry(key).or_default();
+                    match event.kind {
+                        event::KeyEventKind::Press => state.press(event.modifiers),
+                        event::KeyEventKind::Repeat if !state.held => state.press(event.modifiers),
+                        event::KeyEventKind::Release if state.held => state.release(),
+                        _ => {}
+                    }
+                } else {
+                    self.handle_key_without_releases(key, event);
                 }
-                if event.kind == event::KeyEventKind::Release {
-                    self.keymap.insert(event.code, Some(InputEvent::Up));
+            }
+            Event::FocusGained => self.focus_gained = true,
+            Event::FocusLost => {
+                self.focus_lost = true;
+                // Keys let go of while the terminal is in the background are
+                // never reported.
+                for state in self.keymap.values_mut() {
+                    if state.held {
+                        state.release();
+                    }
                 }
             }
+            Event::Mouse(event) => {
+                // Movement only ever updates the position, so any number of
+                // move and drag events in a frame collapse into the last one.
+                let position = (event.column, event.row);
+                if position != self.mouse_position {
+                    self.mouse_moved = true;
+                }
+                self.mouse_position = position;
+                self.modifiers = event.modifiers;
 
-            if let Event::Mouse(event) = event {
-                self.mouse_position = (event.column, event.row);
+                match event.kind {
+                    event::MouseEventKind::Down(button) => self.handle_mouse_down(button, event.modifiers),
+                    event::MouseEventKind::Up(button) => {
+                        self.mousemap.entry(button).or_default().release();
 
-                if let event::MouseEventKind::Down(button) = event.kind {
-                    self.mousemap.insert(button, Some(InputEvent::Down));
+                        if let Some(drag) = &mut self.drag {
+                            if Input::to_crossterm_button(drag.button) == button {
+                                drag.current = position;
+                                drag.ended = true;
+                            }
+                        }
+                    }
+                    event::MouseEventKind::Drag(button) => {
+                        if let Some(drag) = &mut self.drag {
+                            if Input::to_crossterm_button(drag.button) == button && !drag.ended {
+                                drag.started |= !drag.moved;
+                                drag.moved = true;
+                                drag.current = position;
+                            }
+                        }
+                    }
+                    event::MouseEventKind::ScrollUp => self.scroll.1 -= 1,
+                    event::MouseEventKind::ScrollDown => self.scroll.1 += 1,
+                    event::MouseEventKind::ScrollLeft => self.scroll.0 -= 1,
+                    event::MouseEventKind::ScrollRight => self.scroll.0 += 1,
+                    event::MouseEventKind::Moved => {}
                 }
+            }
+            Event::Resize(width, height) => {
+                self.resize = Some((width, height));
+            }
+            _ => {}
+        }
+    }
 
-                if let event::MouseEventKind::Up(button) = event.kind {
-                    self.mousemap.insert(button, Some(InputEvent::Up));
-                }
+    fn handle_mouse_down(&mut self, button: event::MouseButton, modifiers: Modifiers) {
+        let position = self.mouse_position;
+        let state = self.mousemap.entry(button).or_default();
+        state.press(modifiers);
+
+        let double_click = self.last_click.is_some_and(|click| {
+            click.button == button
+                && click.position == position
+                && self.time - click.time <= self.double_click_interval
+        });
+
+        if double_click {
+            state.double_clicks += 1;
+            // A third press starts over, instead of making another double click.
+            self.last_click = None;
+        } else {
+            self.last_click = Some(Click {
+                button,
+                time: self.time,
+                position,
+            });
+        }
+
+        // Only one button is dragged at a time, and pressing another one
+        // while dragging does not interrupt the drag. Pressing the dragged
+        // button again means its release was missed, so the drag starts over.
+        let button = Input::from_crossterm_button(button);
+        if self.drag.is_none_or(|drag| drag.ended || drag.button == button) {
+            self.drag = Some(Drag {
+                button,
+                start: position,
+                current: position,
+                moved: false,
+                started: false,
+                ended: false,
+            });
+        }
+    }
+
+    fn handle_key_without_releases(&mut self, key: Key, event: event::KeyEvent) {
+        let state = self.keymap.entry(key).or_default();
+
+        if event.kind == event::KeyEventKind::Release {
+            if state.held {
+                state.release();
             }
+            return;
+        }
 
-            if let Event::Resize(width, height) = event {
-                self.resize = Some((width, height));
+        let time = self.time;
+        let repeat = self.repeats.entry(key).or_insert(KeyRepeat {
+            last_press: f64::NEG_INFINITY,
+            gap: f64::INFINITY,
+            repeating: false,
+        });
+        let gap = time - repeat.last_press;
+
+        // The first repeat of a held key arrives after a delay, by which time
+        // the key has already been released. It is told apart from a second
+        // press by its timing: repeats follow each other faster than anyone
+        // can tap, and the first one comes after the terminal's repeat delay.
+        // Until that delay has been seen, the first repeat of a held key
+        // counts as a second press.
+        if state.held {
+            repeat.repeating = true;
+        } else if gap <= REPEAT_TIMEOUT {
+            if !repeat.repeating && repeat.gap < REPEAT_DELAY {
+                // The press before this one was the first repeat.
+                self.repeat_delay = Some(repeat.gap);
             }
+            state.hold();
+            repeat.repeating = true;
+        } else if !repeat.repeating && self.repeat_delay.is_some_and(|delay| (gap - delay).abs() <= REPEAT_DELAY_TOLERANCE) {
+            state.hold();
+            repeat.repeating = true;
+        } else {
+            state.press(event.modifiers);
+            repeat.repeating = false;
         }
 
-        Ok(())
+        repeat.gap = gap;
+        repeat.last_press = time;
+    }
+
+    /// Releases held keys that have not repeated recently. A key that has not
+    /// repeated at all is released the frame after it was pressed.
+    fn release_unrepeated_keys(&mut self) {
+        let time = self.time;
+
+        for (key, state) in self.keymap.iter_mut() {
+            let Some(repeat) = self.repeats.get_mut(key) else {
+                continue;
+            };
+
+            let timeout = if repeat.repeating { REPEAT_TIMEOUT } else { 0.0 };
+            if state.held && time - repeat.last_press > timeout {
+                state.release();
+            }
+        }
+
+        self.repeats.retain(|_, repeat| time - repeat.last_press < REPEAT_DELAY);
+    }
+
+    fn from_crossterm_button(button: event::MouseButton) -> MouseButton {
+        match button {
+            event::MouseButton::Left => MouseButton::Left,
+            event::MouseButton::Right => MouseButton::Right,
+            event::MouseButton::Middle => MouseButton::Middle,
+        }
+    }
+
+    fn to_crossterm_button(button: MouseButton) -> event::MouseButton {
+        match button {
+            MouseButton::Left => event::MouseButton::Left,
+            MouseButton::Right => event::MouseButton::Right,
+            MouseButton::Middle => event::MouseButton::Middle,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseEvent, MouseEventKind};
+    use crate::backend::ScriptedEvents;
+    use super::*;
+
+    const FRAME: f64 = 1.0 / 60.0;
+
+    fn scripted(key_releases: bool) -> (Input, Rc<RefCell<VecDeque<Event>>>) {
+        let source = ScriptedEvents::new();
+        let events = source.queue();
+        let mut input = Input::with_source(Box::new(source));
+        input.set_key_releases(key_releases);
+        (input, events)
+    }
+
+    fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent::new_with_kind(code, Modifiers::NONE, kind))
+    }
+
+    fn mouse(kind: MouseEventKind) -> Event {
+        Event::Mouse(MouseEvent { kind, column: 3, row: 4, modifiers: Modifiers::NONE })
+    }
+
+    fn is_key_up(input: &Input, key: impl Into<Key>) -> bool {
+        input.key_state(key).is_some_and(ButtonState::is_up)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn key_is_pressed_then_held_then_released() {
+        let (mut input, events) = scripted(true);
+
+        events.borrow_mut().push_back(key(KeyCode::Left, KeyEventKind::Press));
+        input.update(FRAME).unwrap();
+        assert!(input.is_key_pressed(Key::Left));
+        assert!(input.is_key_held(Key::Left));
+        assert!(!is_key_up(&input, Key::Left));
+
+        events.borrow_mut().push_back(key(KeyCode::Left, KeyEventKind::Repeat));
+        input.update(FRAME).unwrap();
+        assert!(!input.is_key_pressed(Key::Left));
+        assert!(input.is_key_held(Key::Left));
+
+        events.borrow_mut().push_back(key(KeyCode::Left, KeyEventKind::Release));
+        input.update(FRAME).unwrap();
+        assert!(!input.is_key_pressed(Key::Left));
+        assert!(!input.is_key_held(Key::Left));
+        assert!(is_key_up(&input, Key::Left));
+
+        input.update(FRAME).unwrap();
+        assert!(input.key_state(Key::Left).is_none_or(|state| state.event().is_none()));
+    }
+
+    #[test]
+    fn key_pressed_and_released_in_one_frame_is_both() {
+        let (mut input, events) = scripted(true);
+
+        events.borrow_mut().extend([
+            key(KeyCode::Char('x'), KeyEventKind::Press),
+            key(KeyCode::Char('x'), KeyEventKind::Release),
+        ]);
+        input.update(FRAME).unwrap();
+        assert!(input.is_key_pressed('x'));
+        assert!(is_key_up(&input, 'x'));
+        assert!(!input.is_key_held('x'));
+    }
+
+    #[test]
+    fn mouse_button_is_pressed_then_held_then_released() {
+        let (mut input, events) = scripted(true);
+
+        events.borrow_mut().push_back(mouse(MouseEventKind::Down(event::MouseButton::Left)));
+        input.update(FRAME).unwrap();
+        assert!(input.is_mouse_pressed(MouseButton::Left));
+        assert!(input.is_mouse_down(MouseButton::Left));
+        assert!(!input.is_mouse_up(MouseButton::Left));
+        assert_eq!(input.mouse_position(), (3, 4));
+
+        input.update(FRAME).unwrap();
+        assert!(!input.is_mouse_pressed(MouseButton::Left));
+        assert!(input.is_mouse_down(MouseButton::Left));
+        assert!(!input.is_mouse_down(MouseButton::Right));
+
+        events.borrow_mut().push_back(mouse(MouseEventKind::Up(event::MouseButton::Left)));
+        input.update(FRAME).unwrap();
+        assert!(!input.is_mouse_down(MouseButton::Left));
+        assert!(input.is_mouse_up(MouseButton::Left));
+
+        input.update(FRAME).unwrap();
+        assert!(!input.is_mouse_up(MouseButton::Left));
+        assert!(input.mouse_state(MouseButton::Left).is_none());
+    }
+
+    /// Runs `frames` frames, with a press of Left at the start of each frame
+    /// in `presses`, like a terminal without key releases. Returns the frames
+    /// where Left was pressed, and those where it was held.
+    fn press_left(input: &mut Input, events: &Rc<RefCell<VecDeque<Event>>>, presses: &[usize], frames: usize) -> (Vec<usize>, Vec<usize>) {
+        let (mut pressed, mut held) = (Vec::new(), Vec::new());
+        for frame in 0..frames {
+            if presses.contains(&frame) {
+                events.borrow_mut().push_back(key(KeyCode::Left, KeyEventKind::Press));
+            }
+            input.update(FRAME).unwrap();
+            if input.is_key_pressed(Key::Left) {
+                pressed.push(frame);
+            }
+            if input.is_key_held(Key::Left) {
+                held.push(frame);
+            }
+        }
+        (pressed, held)
+    }
+
+    /// A press, then the terminal repeating it after half a second, every
+    /// other frame for another half second.
+    fn hold() -> Vec<usize> {
+        [0].into_iter().chain((30..=60).step_by(2)).collect()
+    }
+
+    #[test]
+    fn quick_taps_without_releases_are_separate_presses() {
+        let (mut input, events) = scripted(false);
+
+        let (pressed, _) = press_left(&mut input, &events, &[0, 15, 40], 60);
+        assert_eq!(pressed, [0, 15, 40]);
+    }
+
+    #[test]
+    fn held_key_without_releases_is_pressed_once_repeat_delay_is_known() {
+        let (mut input, events) = scripted(false);
+
+        // The first time, the first repeat can't be told from a second press.
+        let (pressed, held) = press_left(&mut input, &events, &hold(), 120);
+        assert_eq!(pressed, [0, 30]);
+        assert!((32..=60).all(|frame| held.contains(&frame)));
+        assert!(!held.contains(&70));
+
+        let (pressed, held) = press_left(&mut input, &events, &hold(), 120);
+        assert_eq!(pressed, [0]);
+        assert!((30..=60).all(|frame| held.contains(&frame)));
+
+        // Tapping twice within the repeat delay still presses twice.
+        let (pressed, _) = press_left(&mut input, &events, &[0, 15], 60);
+        assert_eq!(pressed, [0, 15]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use serde::Deserialize;
+
+/// The language everything is written in first. Text missing from another
+/// language is taken from this one.
+pub const DEFAULT_LANGUAGE: &str = "nb";
+
+/// A message in a catalog. Plural messages have a form for each kind of
+/// number, picked by the count given to `Locale::plural`.
+enum Message {
+    Text(String),
+    Plural {
+        zero: Option<String>,
+        one: String,
+        other: String,
+    },
+}
+
+/// The messages of one language, read from a file in `content/locales`.
+///
+/// Catalogs are TOML files where every string is a message, named by its
+/// table and key, like `calendar.locked`. A table with `one` and `other`, and
+/// optionally `zero`, is a plural message. Arguments are written in braces,
+/// like `Luke {door}`.
+pub struct Catalog {
+    messages: HashMap<String, Message>,
+}
+
+impl Catalog {
+    pub fn parse(text: &str) -> Result<Catalog, String> {
+        let table: toml::Table = toml::from_str(text).map_err(|error| error.to_string())?;
+
+        let mut catalog = Catalog { messages: HashMap::new() };
+        catalog.add_table("", table)?;
+        Ok(catalog)
+    }
+
+    fn add_table(&mut self, prefix: &str, table: toml::Table) -> Result<(), String> {
+        for (key, value) in table {
+            let key = format!("{}{}", prefix, key);
+            match value {
+                toml::Value::String(text) => {
+                    self.messages.insert(key, Message::Text(text));
+                }
+                toml::Value::Table(table) if is_plural(&table) => {
+                    let form = |name: &str| match table.get(name) {
+                        Some(toml::Value::String(text)) => Ok(Some(text.clone())),
+                        None => Ok(None),
+                        Some(_) => Err(format!("{}.{} is not a string", key, name)),
+                    };
+                    let message = Message::Plural {
+                        zero: form("zero")?,
+                        one: form("one")?.ok_or_else(|| format!("{} has no 'one' form", key))?,
+                        other: form("other")?.ok_or_else(|| format!("{} has no 'other' form", key))?,
+                    };
+                    self.messages.insert(key, message);
+                }
+                toml::Value::Table(table) => self.add_table(&format!("{}.", key), table)?,
+                _ => return Err(format!("{} is not a string or a table", key)),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_plural(table: &toml::Table) -> bool {
+    table.contains_key("other") && table.keys().all(|key| matches!(key.as_str(), "zero" | "one" | "other"))
+}
+
+/// The catalogs of every language, and the language the player has chosen.
+pub struct Locale {
+    /// Catalogs by language code, like `nb` or `en`.
+    catalogs: BTreeMap<String, Catalog>,
+    language: String,
+}
+
+impl Locale {
+    pub fn new(catalogs: BTreeMap<String, Catalog>, language: &str) -> Result<Locale, String> {
+        if !catalogs.contains_key(DEFAULT_LANGUAGE) {
+            return Err(format!("there is no catalog for the default language '{}'", DEFAULT_LANGUAGE));
+        }
+
+        let mut locale = Locale {
+            catalogs,
+            language: DEFAULT_LANGUAGE.to_string(),
+        };
+        locale.set_language(language)?;
+        Ok(locale)
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn set_language(&mut self, language: &str) -> Result<(), String> {
+        if !self.catalogs.contains_key(language) {
+            let languages: Vec<&str> = self.catalogs.keys().map(String::as_str).collect();
+            return Err(format!("unknown language '{}', expected one of {}", language, languages.join(", ")));
+        }
+        self.language = language.to_string();
+        Ok(())
+    }
+
+    /// What `language` calls itself, like `Norsk`.
+    pub fn language_name(&self, language: &str) -> String {
+        match self.catalogs.get(language).and_then(|catalog| catalog.messages.get("language.name")) {
+            Some(Message::Text(name)) => name.clone(),
+            _ => language.to_string(),
+        }
+    }
+
+    /// The language after the current one, going round.
+    pub fn next_language(&self) -> &str {
+        self.catalogs
+            .keys()
+            .skip_while(|language| **language != self.language)
+            .nth(1)
+            .or_else(|| self.catalogs.keys().next())
+            .unwrap()
+    }
+
+    /// The chosen language, and the one to fall back on.
+    fn languages(&self) -> [&str; 2] {
+        [&self.language, DEFAULT_LANGUAGE]
+    }
+
+    fn message(&self, key: &str) -> Option<&Message> {
+        self.languages()
+            .into_iter()
+            .find_map(|language| self.catalogs[language].messages.get(key))
+    }
+
+    /// The message named `key`. Messages missing from every catalog come out
+    /// as their key, so they are easy to spot.
+    pub fn text(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    /// The message named `key`, with every `{name}` replaced by the argument
+    /// of that name. Plural messages take their `other` form; use `plural`
+    /// to pick the form by a number.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        match self.message(key) {
+            Some(Message::Text(text)) => substitute(text, args),
+            Some(Message::Plural { other, .. }) => substitute(other, args),
+            None => key.to_string(),
+        }
+    }
+
+    /// Like `format`, but plural messages pick their form by `count`, which
+    /// is also the `{count}` argument.
+    pub fn plural(&self, key: &str, count: i64, args: &[(&str, &dyn Display)]) -> String {
+        let template = match self.message(key) {
+            Some(Message::Text(text)) => text,
+            Some(Message::Plural { zero: Some(zero), .. }) if count == 0 => zero,
+            Some(Message::Plural { one, .. }) if count == 1 => one,
+            Some(Message::Plural { other, .. }) => other,
+            None => return key.to_string(),
+        };
+
+        let mut all_args: Vec<(&str, &dyn Display)> = vec![("count", &count)];
+        all_args.extend_from_slice(args);
+        substitute(template, &all_args)
+    }
+}
+
+/// Replaces every `{name}` in `template` by the argument of that name, in a
+/// single pass, so braces in the arguments are left as they are. Names
+/// without an argument are kept as written.
+fn substitute(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let argument = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            args.iter().find(|(arg, _)| *arg == name).map(|(_, value)| (value, end))
+        });
+
+        match argument {
+            Some((value, end)) => {
+                text.push_str(&value.to_string());
+                rest = &after[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Text in content files, either the same in every language or written out
+/// for each, like `{ nb = "Juletreet", en = "The Christmas tree" }`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Text {
+    Plain(String),
+    Translated(BTreeMap<String, String>),
+}
+
+impl Text {
+    /// The text in the chosen language, or the default one, or else any
+    /// language it is written in.
+    pub fn get(&self, locale: &Locale) -> &str {
+        match self {
+            Text::Plain(text) => text,
+            Text::Translated(texts) => locale
+                .languages()
+                .into_iter()
+                .find_map(|language| texts.get(language))
+                .or_else(|| texts.values().next())
+                .map_or("", String::as_str),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(language: &str) -> Locale {
+        let mut catalogs = BTreeMap::new();
+        catalogs.insert("nb".to_string(), Catalog::parse(r#"
+            [test]
+            greeting = "Hei, {name}!"
+            only_norwegian = "Bare på norsk"
+            days = { zero = "Ingen dager", one = "{count} dag", other = "{count} dager" }
+        "#).unwrap());
+        catalogs.insert("en".to_string(), Catalog::parse(r#"
+            [test]
+            greeting = "Hello, {name}!"
+            days = { one = "{count} day", other = "{count} days" }
+        "#).unwrap());
+        Locale::new(catalogs, language).unwrap()
+    }
+
+    #[test]
+    fn plurals_pick_their_form_by_count() {
+        let locale = locale("nb");
+
+        assert_eq!(locale.plural("test.days", 0, &[]), "Ingen dager");
+        assert_eq!(locale.plural("test.days", 1, &[]), "1 dag");
+        assert_eq!(locale.plural("test.days", 2, &[]), "2 dager");
+        assert_eq!(locale.plural("test.days", -1, &[]), "-1 dager");
+    }
+
+    #[test]
+    fn plurals_without_a_zero_form_use_other() {
+        assert_eq!(locale("en").plural("test.days", 0, &[]), "0 days");
+    }
+
+    #[test]
+    fn missing_messages_fall_back_to_the_default_language() {
+        assert_eq!(locale("en").text("test.only_norwegian"), "Bare på norsk");
+    }
+
+    #[test]
+    fn missing_keys_come_out_as_the_key() {
+        assert_eq!(locale("en").text("test.nowhere"), "test.nowhere");
+        assert_eq!(locale("en").plural("test.nowhere", 3, &[]), "test.nowhere");
+    }
+
+    #[test]
+    fn arguments_are_not_substituted_again() {
+        let locale = locale("en");
+
+        assert_eq!(locale.format("test.greeting", &[("name", &"{name}")]), "Hello, {name}!");
+        assert_eq!(substitute("{a}{b}", &[("a", &"{b}"), ("b", &"x")]), "{b}x");
+    }
+
+    #[test]
+    fn unknown_names_are_kept() {
+        assert_eq!(substitute("{missing} {x} {", &[("x", &1)]), "{missing} 1 {");
+    }
+}
@@ -1,21 +1,53 @@
 extern crate crossterm;
-mod ascii;
+mod actions;
+mod ansi;
+mod art;
+mod backend;
+mod calendar;
+mod cli;
 mod screen;
 mod snowflakes;
 mod drawing;
 mod cannon_game;
+mod config;
+mod content;
+mod context;
 mod state_machine;
 mod input;
+mod locale;
 mod transition;
 mod states;
+mod timing;
+mod replay;
+mod save;
+mod quiz;
+mod watch;
 
+use crate::actions::{Action, ActionMap};
+use crate::backend::{Backend, CrosstermBackend, ScriptedEvents};
+use crate::calendar::Calendar;
+use crate::cli::Args;
+use crate::config::Config;
+use crate::content::Content;
+use crate::context::Context;
+use crate::locale::Locale;
 use crate::screen::Screen;
-use crossterm::terminal;
-use std::io::{stdout, Error};
+use crate::state_machine::State;
+use clap::{CommandFactory, Parser};
+use std::io::{stdout, Error, ErrorKind};
+use std::path::PathBuf;
 use std::time::Instant;
 
-use drawing::draw_debug_info;
+use drawing::{draw_debug_info, draw_error};
+use crate::states::Days;
+use crate::states::main_state::MainState;
 use crate::states::transition_state::TransitionState;
+use crate::replay::{Recorder, Replay, Session};
+use crate::save::Progress;
+use crate::timing::{FixedTimestep, FrameLimiter};
+use crate::watch::Watcher;
+
+const FIXED_TIMESTEP: f64 = 1.0 / 120.0;
 
 fn delta_time(previous_time: &mut Instant) -> f64 {
     let new_time = Instant::now();
@@ -24,43 +56,254 @@ fn delta_time(previous_time: &mut Instant) -> f64 {
     dt
 }
 
+/// Reads the content again and puts it in the context, keeping the language.
+/// Nothing changes unless all of it reads.
+fn reload(ctx: &mut Context, content: &Content) -> Result<(), Error> {
+    let art = content.art()?;
+    let days = Days::new(content.quizzes(&art)?)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    let locale = Locale::new(content.catalogs()?, ctx.locale.language())
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+
+    ctx.art = art;
+    ctx.days = days;
+    ctx.locale = locale;
+    Ok(())
+}
+
 fn main() -> Result<(), Error> {
-    let mut input = input::Input::new();
+    let args = Args::parse();
+
+    // Watching the built in files would never see a change, so watching
+    // reads the files in the working directory instead, like when running
+    // from the root of the project.
+    let content_dir = args.content.clone().or_else(|| args.watch.then(|| PathBuf::from("content")));
+    let assets_dir = args.assets.clone().or_else(|| args.watch.then(|| PathBuf::from("assets")));
+
+    let mut content = match content_dir {
+        Some(dir) => Content::from_dir(dir),
+        None => Content::embedded(),
+    };
+    if let Some(dir) = assets_dir {
+        content = content.assets(dir);
+    }
+    let art = content.art()?;
+    let days = Days::new(content.quizzes(&art)?)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    if let Some(door) = args.day.filter(|door| days.get(*door).is_none()) {
+        Args::command()
+            .error(clap::error::ErrorKind::InvalidValue, format!("there is no day behind door {}", door))
+            .exit();
+    }
 
-    let mut screen = Screen::new(stdout(), terminal::size()?);
+    let mut config = Config::load()?;
+    if let Some(fps) = args.fps {
+        config.display.fps = fps;
+    }
+    if args.no_transitions {
+        config.display.transitions = false;
+    }
+
+    let mut locale = Locale::new(content.catalogs()?, &config.display.language)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    if let Some(language) = &args.language {
+        if let Err(error) = locale.set_language(language) {
+            Args::command().error(clap::error::ErrorKind::InvalidValue, error).exit();
+        }
+    }
+
+    let actions = ActionMap::from_config(&config.bindings)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    let mut calendar = Calendar::new(&config.calendar)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    if let Some(date) = args.date {
+        calendar.set_date(date);
+    }
+
+    let mut replay = match &args.replay {
+        Some(path) => Some(Replay::load(path)?),
+        None => None,
+    };
+
+    if args.reset_progress {
+        Progress::reset()?;
+    }
+
+    // Replays start from scratch and leave the real progress alone.
+    let progress = match replay {
+        Some(_) => Progress::in_memory(),
+        None => Progress::load()?,
+    };
+
+    let replay_events = ScriptedEvents::new();
+    let replay_queue = replay_events.queue();
+
+    let mut input = match replay {
+        Some(_) => input::Input::with_source(Box::new(replay_events)),
+        None => input::Input::new(),
+    };
+    input.set_actions(actions);
+    input.set_double_click_interval(config.mouse.double_click_interval);
+
+    let backend = CrosstermBackend::new(stdout());
+    let size = backend.size()?;
+    let mut screen = Screen::new(Box::new(backend), size);
     screen.init()?;
 
-    let initial_state = TransitionState::new(Box::new(states::main_state::MainState::new()), Some(transition::TransitionDirection::Out));
+    let mut day = args.day;
+    match &replay {
+        Some(replay) => {
+            let session = replay.session();
+            screen.resize(session.size);
+            input.set_key_releases(session.key_releases);
+            if let Some(date) = session.date {
+                calendar.set_date(date);
+            }
+            day = session.day;
+            config.display.transitions = session.transitions;
+            if let Some(language) = &session.language {
+                locale.set_language(language).map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+            }
+        }
+        None => input.set_key_releases(screen.reports_key_releases()),
+    }
+
+    let seed = replay
+        .as_ref()
+        .and_then(|replay| replay.session().seed)
+        .or(args.seed)
+        .unwrap_or_else(rand::random);
+
+    if let Some(path) = &args.record {
+        input.record(Recorder::create(path, &Session {
+            size,
+            key_releases: screen.reports_key_releases(),
+            seed: Some(seed),
+            date: Some(calendar.today()),
+            day,
+            transitions: config.display.transitions,
+            language: Some(locale.language().to_string()),
+        })?);
+    }
+
+    let mut ctx = Context::new(screen, input, config, calendar, progress, days, locale, art, seed);
+
+    let first_state: Box<dyn State> = match day.and_then(|door| ctx.days.get(door).cloned()) {
+        Some(day) => {
+            ctx.progress.attempt(day.door());
+            day.create(&mut ctx)
+        }
+        None => Box::new(MainState::new()),
+    };
+    let initial_state = TransitionState::new(first_state, Some(transition::TransitionDirection::Out))
+        .effect(transition::SnowCurtain::new())
+        .easing(transition::Easing::Linear);
     let mut state_machine = state_machine::StateMachine::new();
-    state_machine.change(&mut screen, &mut input, Some(Box::new(initial_state)));
+    state_machine.push(&mut ctx, Box::new(initial_state));
 
     let mut dt;
     let mut previous_time = Instant::now();
+    let mut frame_limiter = FrameLimiter::new(Some(ctx.config.display.fps));
+    let mut timestep = FixedTimestep::new(FIXED_TIMESTEP);
+    let mut watcher = args.watch.then(|| Watcher::new(content.dirs()));
+    let mut reload_error = None;
+    let mut save_error = None;
 
     loop {
-        if input.is_key_up('q') {
+        dt = delta_time(&mut previous_time);
+
+        if let Some(replay) = &mut replay {
+            let Some(frame) = replay.next_frame() else {
+                break;
+            };
+            dt = frame.dt;
+            replay_queue.borrow_mut().extend(frame.events);
+        }
+
+        ctx.input.update(dt)?;
+        ctx.clock.tick(dt);
+
+        if ctx.input.is_action_pressed(Action::Quit) || state_machine.is_empty() {
             break;
         }
 
-        if let Some(size) = input.resized() {
-            screen.resize(size);
+        if watcher.as_mut().is_some_and(|watcher| watcher.poll(dt)) {
+            reload_error = match reload(&mut ctx, &content) {
+                Ok(()) => {
+                    state_machine.reload(&mut ctx);
+                    None
+                }
+                Err(error) => Some(error.to_string()),
+            };
         }
 
-        screen.clear();
+        if let Some(size) = ctx.input.resized() {
+            ctx.screen.resize(size);
+            state_machine.resize(&mut ctx);
+        }
 
-        dt = delta_time(&mut previous_time);
+        // Handled before the screen is cleared, so a state pushed in response
+        // can freeze the last frame under it.
+        if ctx.input.focus_lost() {
+            state_machine.focus_lost(&mut ctx);
+        }
+        if ctx.input.focus_gained() {
+            state_machine.focus_gained(&mut ctx);
+        }
 
-        state_machine.update(&mut screen, &mut input, dt);
+        ctx.screen.clear();
 
-        if cfg!(debug_assertions) {
-            draw_debug_info(&mut screen, &mut input, dt);
+        for _ in 0..timestep.advance(dt) {
+            state_machine.fixed_update(&mut ctx, timestep.step());
         }
 
-        screen.render();
+        state_machine.update(&mut ctx, dt);
+        // Playing on is better than losing the session to a full disk, so
+        // the error is shown until a later write goes through.
+        match ctx.progress.save_if_changed() {
+            Ok(()) if !ctx.progress.has_unsaved_changes() => save_error = None,
+            Ok(()) => {}
+            Err(error) => save_error = Some(error.to_string()),
+        }
+
+        if args.debug {
+            draw_debug_info(&mut ctx.screen, &mut ctx.input, &ctx.clock, dt);
+        }
+
+        let mut bottom = ctx.screen.height();
+        if let Some(error) = &reload_error {
+            bottom = draw_error(&mut ctx.screen, &ctx.locale.text("error.reload"), error, bottom);
+        }
+        if let Some(error) = &save_error {
+            draw_error(&mut ctx.screen, &ctx.locale.text("error.save"), error, bottom);
+        }
+
+        ctx.screen.render();
 
-        input.update()?;
+        frame_limiter.wait();
     }
 
-    screen.cleanup()?;
+    ctx.input.finish_recording()?;
+    ctx.screen.cleanup()?;
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::context::testing::Harness;
+    use super::*;
+
+    #[test]
+    fn reloading_art_without_a_required_anchor_keeps_the_old_content() {
+        let mut harness = Harness::new((80, 24));
+        let dir = std::env::temp_dir().join(format!("julekalender-reload-{}", std::process::id()));
+        fs::create_dir_all(dir.join("art")).unwrap();
+        fs::write(dir.join("art").join("santa_face.txt"), "(o_o)").unwrap();
+
+        let result = reload(&mut harness.ctx, &Content::embedded().assets(dir.clone()));
+        fs::remove_dir_all(dir).unwrap();
+
+        assert!(result.unwrap_err().to_string().contains("no anchor 'peek'"));
+        assert!(harness.ctx.art["santa_face"].anchor("peek").is_some());
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use crate::art::ArtLibrary;
+use crate::drawing::CALENDAR_DOORS;
+use crate::locale::Text;
+
+const DEFAULT_AUTHOR: &str = "Faggruppe Rust";
+
+/// Seconds to answer each question, unless the quiz says otherwise.
+const DEFAULT_TIME_LIMIT: f64 = 20.0;
+
+/// A quiz day, read from a file in `content/quizzes`. The format is described
+/// in the README, so the questions can be written without touching Rust. All
+/// the text can be translated, see `Text`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Quiz {
+    pub door: usize,
+    pub title: Text,
+    #[serde(default = "default_author")]
+    pub author: String,
+    /// The day in December the door opens. The door's own day if left out.
+    unlocks_on: Option<usize>,
+    /// Seconds to answer each question, unless the question says otherwise.
+    #[serde(default = "default_time_limit")]
+    time_limit: f64,
+    /// The questions of the round, asked in order.
+    pub questions: Vec<Question>,
+    /// Effects drawn over the whole screen.
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+    /// Art drawn behind the question, in order.
+    #[serde(default)]
+    pub art: Vec<Art>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Question {
+    pub question: Text,
+    pub correct_answer: Text,
+    pub wrong_answers: Vec<Text>,
+    /// Why the answer is correct, shown once the question is answered.
+    pub explanation: Option<Text>,
+    time_limit: Option<f64>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    Snow,
+}
+
+/// A piece of art from `assets/art`, placed on the screen.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Art {
+    pub name: String,
+    /// The anchor of the art that is placed at `x`, `y`.
+    #[serde(default = "default_anchor")]
+    pub anchor: String,
+    /// Columns from the left edge, or from the right edge if negative, so -1
+    /// is the last column.
+    #[serde(default)]
+    x: i16,
+    /// Rows from the top, or from the bottom if negative.
+    #[serde(default)]
+    y: i16,
+    /// Whether Z's float up from the art, starting at its `snore` anchor
+    /// if it has one.
+    #[serde(default)]
+    pub snoring: bool,
+}
+
+fn default_author() -> String {
+    DEFAULT_AUTHOR.to_string()
+}
+
+fn default_time_limit() -> f64 {
+    DEFAULT_TIME_LIMIT
+}
+
+fn default_anchor() -> String {
+    "top_left".to_string()
+}
+
+impl Quiz {
+    pub fn unlocks_on(&self) -> usize {
+        self.unlocks_on.unwrap_or(self.door)
+    }
+
+    /// Seconds to answer `question`.
+    pub fn time_limit(&self, question: &Question) -> f64 {
+        question.time_limit.unwrap_or(self.time_limit)
+    }
+}
+
+impl Art {
+    /// Where the anchor of the art goes on a screen of this size.
+    pub fn position(&self, width: u16, height: u16) -> (i16, i16) {
+        (from_edge(self.x, width), from_edge(self.y, height))
+    }
+}
+
+fn from_edge(offset: i16, size: u16) -> i16 {
+    if offset < 0 {
+        size as i16 + offset
+    } else {
+        offset
+    }
+}
+
+pub fn parse(text: &str, library: &ArtLibrary) -> Result<Quiz, String> {
+    let quiz: Quiz = toml::from_str(text).map_err(|error| error.to_string())?;
+
+    if !(1..=CALENDAR_DOORS).contains(&quiz.door) {
+        return Err(format!("door {} is not in the calendar, which has doors 1 to {}", quiz.door, CALENDAR_DOORS));
+    }
+    if quiz.questions.is_empty() {
+        return Err("a quiz needs at least one question".to_string());
+    }
+    for (i, question) in quiz.questions.iter().enumerate() {
+        if question.wrong_answers.is_empty() {
+            return Err(format!("question {} needs at least one wrong answer", i + 1));
+        }
+        if quiz.time_limit(question) <= 0.0 {
+            return Err(format!("question {} needs a time limit above zero", i + 1));
+        }
+    }
+    for art in &quiz.art {
+        let Some(found) = library.get(&art.name) else {
+            return Err(format!("unknown art '{}'", art.name));
+        };
+        if found.anchor(&art.anchor).is_none() {
+            return Err(format!("art '{}' has no anchor '{}'", art.name, art.anchor));
+        }
+    }
+
+    Ok(quiz)
+}
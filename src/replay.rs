@@ -0,0 +1,425 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+use chrono::NaiveDate;
+use crossterm::event;
+use crossterm::event::{Event, KeyEvent, KeyEventKind, KeyEventState, MouseEvent, MouseEventKind};
+use crate::input::{format_modifiers, parse_modifiers, Chord, Key, Modifiers};
+
+/// The first line of every recording. The number is bumped whenever the
+/// format changes in a way older versions cannot read.
+const HEADER: &str = "julekalender-replay 1";
+
+/// How a session was started. A replay starts the same way as the recorded
+/// session did.
+pub struct Session {
+    pub size: (u16, u16),
+    pub key_releases: bool,
+    pub seed: Option<u64>,
+    pub date: Option<NaiveDate>,
+    /// The door opened at start, skipping the calendar.
+    pub day: Option<usize>,
+    pub transitions: bool,
+    pub language: Option<String>,
+}
+
+/// The events of one frame, and how long the frame lasted.
+pub struct Frame {
+    pub dt: f64,
+    pub events: Vec<Event>,
+}
+
+/// Writes every frame `Input` handles to a file, so the session can be played
+/// back with `Replay`.
+///
+/// Recordings are plain text with one entry per line. Blank lines and lines
+/// starting with `#` are ignored, so recordings can be commented and edited by
+/// hand. After the `julekalender-replay 1` header come the settings of the
+/// recorded session:
+///
+/// ```text
+/// size 120 40
+/// key_releases true
+/// seed 1234
+/// date 2024-12-05
+/// day 14
+/// transitions false
+/// language en
+/// ```
+///
+/// Without a seed, the replay gets new random numbers, and only reproduces
+/// the session if nothing random happened in it. Without a date, the calendar
+/// goes by the date the replay is played on. `day` is only there if the
+/// session started behind a door, and transitions are on unless it says
+/// otherwise. Without a language, the replay is shown in the configured one.
+///
+/// Then every frame starts with its `dt` in seconds, followed by the events
+/// handled during the frame, in order:
+///
+/// ```text
+/// frame 0.016667
+/// key press Ctrl+c
+/// key release a
+/// mouse down left 10 5
+/// mouse drag left 12 5 Shift
+/// mouse scroll_down 12 5
+/// resize 100 30
+/// focus lost
+/// ```
+///
+/// Key events are `key <press|repeat|release> <chord>`, with the chord
+/// written as in the config file. Mouse events are `mouse <kind> [button] <x>
+/// <y> [modifiers]`, where the kind is one of `down`, `up`, `drag` (which take
+/// a button), `moved`, `scroll_up`, `scroll_down`, `scroll_left` or
+/// `scroll_right`. Events `Input` ignores, like pastes or keys it has no name
+/// for, are not recorded.
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: impl AsRef<Path>, session: &Session) -> Result<Recorder, Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(writer, "size {} {}", session.size.0, session.size.1)?;
+        writeln!(writer, "key_releases {}", session.key_releases)?;
+        if let Some(seed) = session.seed {
+            writeln!(writer, "seed {}", seed)?;
+        }
+        if let Some(date) = session.date {
+            writeln!(writer, "date {}", date)?;
+        }
+        if let Some(day) = session.day {
+            writeln!(writer, "day {}", day)?;
+        }
+        writeln!(writer, "transitions {}", session.transitions)?;
+        if let Some(language) = &session.language {
+            writeln!(writer, "language {}", language)?;
+        }
+
+        Ok(Recorder { writer })
+    }
+
+    pub fn frame(&mut self, dt: f64) -> Result<(), Error> {
+        // Rust prints the shortest text that parses back to the same f64, so
+        // the replayed dt is exactly the recorded one.
+        writeln!(self.writer, "frame {}", dt)
+    }
+
+    pub fn event(&mut self, event: &Event) -> Result<(), Error> {
+        match format_event(event) {
+            Some(line) => writeln!(self.writer, "{}", line),
+            None => Ok(()),
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+}
+
+/// A recording made by `Recorder`, played back one frame at a time.
+pub struct Replay {
+    session: Session,
+    frames: VecDeque<Frame>,
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Replay, Error> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+        let mut lines = Vec::new();
+        for line in reader.lines() {
+            lines.push(line?);
+        }
+
+        Replay::parse(&lines).map_err(|(number, error)| {
+            Error::new(ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), number, error))
+        })
+    }
+
+    /// Parses the lines of a recording. Errors come with the line number they
+    /// were found on.
+    fn parse(lines: &[String]) -> Result<Replay, (usize, String)> {
+        let mut replay = Replay {
+            session: Session {
+                size: (0, 0),
+                key_releases: false,
+                seed: None,
+                date: None,
+                day: None,
+                transitions: true,
+                language: None,
+            },
+            frames: VecDeque::new(),
+        };
+        let mut has_header = false;
+
+        for (i, line) in lines.iter().enumerate() {
+            let number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if !has_header {
+                if line != HEADER {
+                    return Err((number, format!("expected '{}'", HEADER)));
+                }
+                has_header = true;
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            replay.parse_line(&words).map_err(|error| (number, error))?;
+        }
+
+        if !has_header {
+            return Err((lines.len(), "the recording is empty".to_string()));
+        }
+
+        Ok(replay)
+    }
+
+    fn parse_line(&mut self, words: &[&str]) -> Result<(), String> {
+        match words {
+            ["size", width, height] => {
+                self.session.size = (parse_number(width)?, parse_number(height)?);
+            }
+            ["key_releases", value] => {
+                self.session.key_releases = parse_bool(value)?;
+            }
+            ["seed", seed] => {
+                self.session.seed = Some(seed.parse().map_err(|_| format!("invalid seed '{}'", seed))?);
+            }
+            ["date", date] => {
+                self.session.date = Some(date.parse().map_err(|_| format!("invalid date '{}'", date))?);
+            }
+            ["day", day] => {
+                self.session.day = Some(day.parse().map_err(|_| format!("invalid day '{}'", day))?);
+            }
+            ["transitions", value] => {
+                self.session.transitions = parse_bool(value)?;
+            }
+            ["language", language] => {
+                self.session.language = Some(language.to_string());
+            }
+            ["frame", dt] => {
+                let dt = dt.parse().map_err(|_| format!("invalid frame time '{}'", dt))?;
+                self.frames.push_back(Frame { dt, events: Vec::new() });
+            }
+            _ => {
+                let event = parse_event(words)?;
+                let frame = self.frames.back_mut().ok_or("event before the first frame")?;
+                frame.events.push(event);
+            }
+        }
+        Ok(())
+    }
+
+    /// How the recorded session was started.
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// The next recorded frame, or `None` when the recording is over.
+    pub fn next_frame(&mut self) -> Option<Frame> {
+        self.frames.pop_front()
+    }
+}
+
+fn format_event(event: &Event) -> Option<String> {
+    match event {
+        Event::Key(event) => {
+            let kind = match event.kind {
+                KeyEventKind::Press => "press",
+                KeyEventKind::Repeat => "repeat",
+                KeyEventKind::Release => "release",
+            };
+            let key = Key::from_crossterm(event.code)?;
+            Some(format!("key {} {}", kind, Chord::new(key, event.modifiers)))
+        }
+        Event::Mouse(event) => {
+            let kind = match event.kind {
+                MouseEventKind::Down(button) => format!("down {}", format_button(button)),
+                MouseEventKind::Up(button) => format!("up {}", format_button(button)),
+                MouseEventKind::Drag(button) => format!("drag {}", format_button(button)),
+                MouseEventKind::Moved => "moved".to_string(),
+                MouseEventKind::ScrollUp => "scroll_up".to_string(),
+                MouseEventKind::ScrollDown => "scroll_down".to_string(),
+                MouseEventKind::ScrollLeft => "scroll_left".to_string(),
+                MouseEventKind::ScrollRight => "scroll_right".to_string(),
+            };
+            let line = format!("mouse {} {} {}", kind, event.column, event.row);
+            match format_modifiers(event.modifiers) {
+                modifiers if modifiers.is_empty() => Some(line),
+                modifiers => Some(format!("{} {}", line, modifiers)),
+            }
+        }
+        Event::Resize(width, height) => Some(format!("resize {} {}", width, height)),
+        Event::FocusGained => Some("focus gained".to_string()),
+        Event::FocusLost => Some("focus lost".to_string()),
+        Event::Paste(_) => None,
+    }
+}
+
+fn parse_event(words: &[&str]) -> Result<Event, String> {
+    match words {
+        ["key", kind, chord] => {
+            let kind = match *kind {
+                "press" => KeyEventKind::Press,
+                "repeat" => KeyEventKind::Repeat,
+                "release" => KeyEventKind::Release,
+                _ => return Err(format!("unknown key event '{}'", kind)),
+            };
+            let chord = chord.parse::<Chord>()?;
+            Ok(Event::Key(KeyEvent {
+                code: chord.key.to_crossterm(),
+                modifiers: chord.modifiers,
+                kind,
+                state: KeyEventState::NONE,
+            }))
+        }
+        ["mouse", kind, rest @ ..] => {
+            let (kind, rest) = match (*kind, rest) {
+                ("down", [button, rest @ ..]) => (MouseEventKind::Down(parse_button(button)?), rest),
+                ("up", [button, rest @ ..]) => (MouseEventKind::Up(parse_button(button)?), rest),
+                ("drag", [button, rest @ ..]) => (MouseEventKind::Drag(parse_button(button)?), rest),
+                ("moved", rest) => (MouseEventKind::Moved, rest),
+                ("scroll_up", rest) => (MouseEventKind::ScrollUp, rest),
+                ("scroll_down", rest) => (MouseEventKind::ScrollDown, rest),
+                ("scroll_left", rest) => (MouseEventKind::ScrollLeft, rest),
+                ("scroll_right", rest) => (MouseEventKind::ScrollRight, rest),
+                _ => return Err(format!("unknown mouse event '{}'", kind)),
+            };
+            let (column, row, modifiers) = match rest {
+                [x, y] => (parse_number(x)?, parse_number(y)?, Modifiers::NONE),
+                [x, y, modifiers] => (parse_number(x)?, parse_number(y)?, parse_modifiers(modifiers)?),
+                _ => return Err("expected a mouse position".to_string()),
+            };
+            Ok(Event::Mouse(MouseEvent { kind, column, row, modifiers }))
+        }
+        ["resize", width, height] => Ok(Event::Resize(parse_number(width)?, parse_number(height)?)),
+        ["focus", "gained"] => Ok(Event::FocusGained),
+        ["focus", "lost"] => Ok(Event::FocusLost),
+        _ => Err(format!("unknown entry '{}'", words.join(" "))),
+    }
+}
+
+fn format_button(button: event::MouseButton) -> &'static str {
+    match button {
+        event::MouseButton::Left => "left",
+        event::MouseButton::Right => "right",
+        event::MouseButton::Middle => "middle",
+    }
+}
+
+fn parse_button(name: &str) -> Result<event::MouseButton, String> {
+    match name {
+        "left" => Ok(event::MouseButton::Left),
+        "right" => Ok(event::MouseButton::Right),
+        "middle" => Ok(event::MouseButton::Middle),
+        _ => Err(format!("unknown mouse button '{}'", name)),
+    }
+}
+
+fn parse_bool(text: &str) -> Result<bool, String> {
+    text.parse().map_err(|_| format!("expected true or false, found '{}'", text))
+}
+
+fn parse_number(text: &str) -> Result<u16, String> {
+    text.parse().map_err(|_| format!("invalid number '{}'", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crossterm::event::{KeyCode, MouseButton};
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn parse_error(text: &str) -> (usize, String) {
+        match Replay::parse(&lines(text)) {
+            Ok(_) => panic!("parsed '{}'", text),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn recording_reads_back_the_same() {
+        let events = [
+            Event::Key(KeyEvent::new_with_kind(KeyCode::Char('c'), Modifiers::CONTROL, KeyEventKind::Press)),
+            Event::Key(KeyEvent::new_with_kind(KeyCode::Left, Modifiers::NONE, KeyEventKind::Release)),
+            Event::Mouse(MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column: 10, row: 5, modifiers: Modifiers::NONE }),
+            Event::Mouse(MouseEvent { kind: MouseEventKind::Drag(MouseButton::Left), column: 12, row: 5, modifiers: Modifiers::SHIFT }),
+            Event::Mouse(MouseEvent { kind: MouseEventKind::ScrollDown, column: 12, row: 5, modifiers: Modifiers::NONE }),
+            Event::Resize(100, 30),
+            Event::FocusLost,
+        ];
+
+        let path = std::env::temp_dir().join(format!("julekalender-{}.replay", std::process::id()));
+        let mut recorder = Recorder::create(&path, &Session {
+            size: (120, 40),
+            key_releases: true,
+            seed: Some(1234),
+            date: NaiveDate::from_ymd_opt(2024, 12, 5),
+            day: Some(14),
+            transitions: false,
+            language: Some("en".to_string()),
+        }).unwrap();
+        recorder.frame(1.0 / 60.0).unwrap();
+        for event in &events {
+            recorder.event(event).unwrap();
+        }
+        recorder.event(&Event::Paste("ignored".to_string())).unwrap();
+        recorder.frame(0.1).unwrap();
+        recorder.flush().unwrap();
+        drop(recorder);
+
+        let replay = Replay::load(&path);
+        fs::remove_file(&path).unwrap();
+        let mut replay = replay.unwrap();
+
+        let session = replay.session();
+        assert_eq!(session.size, (120, 40));
+        assert!(session.key_releases);
+        assert_eq!(session.seed, Some(1234));
+        assert_eq!(session.date, NaiveDate::from_ymd_opt(2024, 12, 5));
+        assert_eq!(session.day, Some(14));
+        assert!(!session.transitions);
+        assert_eq!(session.language.as_deref(), Some("en"));
+
+        let frame = replay.next_frame().unwrap();
+        assert_eq!(frame.dt, 1.0 / 60.0);
+        assert_eq!(frame.events, events);
+        let frame = replay.next_frame().unwrap();
+        assert_eq!(frame.dt, 0.1);
+        assert!(frame.events.is_empty());
+        assert!(replay.next_frame().is_none());
+    }
+
+    #[test]
+    fn missing_settings_have_defaults() {
+        let replay = Replay::parse(&lines("# comment\n\njulekalender-replay 1\nsize 80 40\n")).unwrap();
+        let session = replay.session();
+        assert_eq!(session.seed, None);
+        assert_eq!(session.day, None);
+        assert!(session.transitions);
+        assert_eq!(session.language, None);
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        assert_eq!(parse_error(""), (0, "the recording is empty".to_string()));
+        assert_eq!(parse_error("# comment\njulekalender-replay 2"), (2, "expected 'julekalender-replay 1'".to_string()));
+        assert_eq!(parse_error("julekalender-replay 1\nsize 80 forty"), (2, "invalid number 'forty'".to_string()));
+        assert_eq!(parse_error("julekalender-replay 1\n\nfocus lost"), (3, "event before the first frame".to_string()));
+        assert_eq!(parse_error("julekalender-replay 1\nframe 0.1\nkey hold a"), (3, "unknown key event 'hold'".to_string()));
+        assert_eq!(parse_error("julekalender-replay 1\nframe 0.1\nmouse down thumb 1 2"), (3, "unknown mouse button 'thumb'".to_string()));
+        assert_eq!(parse_error("julekalender-replay 1\ntransitions maybe"), (2, "expected true or false, found 'maybe'".to_string()));
+    }
+}
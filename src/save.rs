@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const SAVE_FILE: &str = "progress.toml";
+
+/// The version of the save file this build writes. Bump it whenever the
+/// format changes, and add a step to `MIGRATIONS` that upgrades the previous
+/// version.
+const SAVE_VERSION: u32 = 1;
+
+/// Upgrades the raw contents of a save file by one version.
+type Migration = fn(&mut toml::Table) -> Result<(), String>;
+
+/// Upgrades older save files one version at a time. The first step turns
+/// version 1 into version 2, and so on.
+const MIGRATIONS: &[Migration] = &[];
+
+/// How long to wait before trying again after the progress could not be
+/// written, so a full disk is not written to every frame.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How a day went when it was completed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    /// Completed, with nothing to compare.
+    Done,
+    /// Completed in this many seconds. Lower is better.
+    Time(f64),
+    /// Completed with this many points. Higher is better.
+    Score(u32),
+    /// Completed in this many moves. Lower is better.
+    Moves(u32),
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct DayProgress {
+    /// How many times the door has been opened.
+    pub attempts: u32,
+    /// How many times the day has been completed.
+    pub completions: u32,
+    pub best_time: Option<f64>,
+    pub best_score: Option<u32>,
+    pub fewest_moves: Option<u32>,
+    pub first_completed: Option<DateTime<Utc>>,
+}
+
+impl DayProgress {
+    pub fn is_completed(&self) -> bool {
+        self.completions > 0
+    }
+}
+
+/// Everything in the save file.
+#[derive(Serialize, Deserialize, Debug)]
+struct SaveData {
+    version: u32,
+    /// Progress by door number. TOML only has string keys.
+    days: BTreeMap<String, DayProgress>,
+}
+
+/// The player's progress through the calendar, kept in `progress.toml` in the
+/// julekalender folder of the platform's data directory (`~/.local/share`
+/// on Linux). Changes are written by `save_if_changed`.
+pub struct Progress {
+    data: SaveData,
+    /// Where the progress is saved. Progress without a path, like during a
+    /// replay, is thrown away at exit.
+    path: Option<PathBuf>,
+    changed: bool,
+    /// When to try again after a failed write.
+    retry_at: Option<Instant>,
+}
+
+impl Progress {
+    pub fn load() -> Result<Progress, Error> {
+        let Some(path) = Progress::path() else {
+            return Ok(Progress::in_memory());
+        };
+
+        let data = match fs::read_to_string(&path) {
+            Ok(text) => parse(&text).map_err(|error| {
+                Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), error))
+            })?,
+            Err(error) if error.kind() == ErrorKind::NotFound => SaveData::new(),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Progress {
+            data,
+            path: Some(path),
+            changed: false,
+            retry_at: None,
+        })
+    }
+
+    /// Progress that starts out empty and is never saved.
+    pub fn in_memory() -> Progress {
+        Progress {
+            data: SaveData::new(),
+            path: None,
+            changed: false,
+            retry_at: None,
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("julekalender").join(SAVE_FILE))
+    }
+
+    /// Deletes the saved progress, if there is any.
+    pub fn reset() -> Result<(), Error> {
+        let Some(path) = Progress::path() else {
+            return Ok(());
+        };
+
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn day(&self, door: usize) -> Option<&DayProgress> {
+        self.data.days.get(&door.to_string())
+    }
+
+    fn day_mut(&mut self, door: usize) -> &mut DayProgress {
+        self.changed = true;
+        self.data.days.entry(door.to_string()).or_default()
+    }
+
+    /// Counts an opening of `door`.
+    pub fn attempt(&mut self, door: usize) {
+        self.day_mut(door).attempts += 1;
+    }
+
+    /// Records that the day behind `door` was completed, keeping the best
+    /// result so far.
+    pub fn complete(&mut self, door: usize, outcome: Outcome) {
+        let day = self.day_mut(door);
+        day.completions += 1;
+        day.first_completed.get_or_insert_with(Utc::now);
+
+        match outcome {
+            Outcome::Done => {}
+            Outcome::Time(time) => {
+                day.best_time = Some(day.best_time.map_or(time, |best| best.min(time)));
+            }
+            Outcome::Score(score) => {
+                day.best_score = Some(day.best_score.map_or(score, |best| best.max(score)));
+            }
+            Outcome::Moves(moves) => {
+                day.fewest_moves = Some(day.fewest_moves.map_or(moves, |best| best.min(moves)));
+            }
+        }
+    }
+
+    /// Whether there are changes that have not been written yet.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.changed
+    }
+
+    /// Writes the progress to disk if anything changed since it was last
+    /// written. The changes are kept if the write fails, and written on a
+    /// later call once `RETRY_DELAY` has passed.
+    pub fn save_if_changed(&mut self) -> Result<(), Error> {
+        if !self.changed || self.retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+            return Ok(());
+        }
+
+        let result = match &self.path {
+            Some(path) => toml::to_string(&self.data)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error))
+                .and_then(|text| write_atomically(path, &text)),
+            None => Ok(()),
+        };
+
+        match result {
+            Ok(()) => {
+                self.changed = false;
+                self.retry_at = None;
+            }
+            Err(_) => self.retry_at = Some(Instant::now() + RETRY_DELAY),
+        }
+        result
+    }
+}
+
+impl SaveData {
+    fn new() -> SaveData {
+        SaveData {
+            version: SAVE_VERSION,
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+/// Reads a save file of any version up to the current one.
+fn parse(text: &str) -> Result<SaveData, String> {
+    let mut table: toml::Table = toml::from_str(text).map_err(|error| error.to_string())?;
+
+    let mut version = match table.get("version") {
+        Some(toml::Value::Integer(version)) => u32::try_from(*version).map_err(|_| format!("invalid version {}", version))?,
+        _ => return Err("missing version".to_string()),
+    };
+    if version == 0 || version > SAVE_VERSION {
+        return Err(format!("unsupported version {}, this build reads up to version {}", version, SAVE_VERSION));
+    }
+
+    while version < SAVE_VERSION {
+        MIGRATIONS[version as usize - 1](&mut table)
+            .map_err(|error| format!("upgrading from version {}: {}", version, error))?;
+        version += 1;
+    }
+    table.insert("version".to_string(), toml::Value::Integer(version as i64));
+
+    table.try_into().map_err(|error: toml::de::Error| error.to_string())
+}
+
+/// Replaces the file at `path` without ever leaving a half-written file
+/// behind, by writing next to it and renaming over it.
+fn write_atomically(path: &Path, contents: &str) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temporary = path.with_extension("toml.tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_version_1() {
+        let data = parse("version = 1\n\n[days.3]\nattempts = 2\ncompletions = 1\nbest_time = 12.5\n").unwrap();
+
+        assert_eq!(data.version, 1);
+        let day = &data.days["3"];
+        assert_eq!(day.attempts, 2);
+        assert_eq!(day.completions, 1);
+        assert_eq!(day.best_time, Some(12.5));
+        assert_eq!(day.best_score, None);
+    }
+
+    #[test]
+    fn rejects_version_0() {
+        assert_eq!(parse("version = 0\n[days]\n").unwrap_err(), "unsupported version 0, this build reads up to version 1");
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let text = format!("version = {}\n[days]\n", SAVE_VERSION + 1);
+        assert_eq!(
+            parse(&text).unwrap_err(),
+            format!("unsupported version {}, this build reads up to version {}", SAVE_VERSION + 1, SAVE_VERSION),
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_version() {
+        assert_eq!(parse("[days.1]\nattempts = 1\n").unwrap_err(), "missing version");
+    }
+
+    #[test]
+    fn changes_are_kept_when_the_write_fails() {
+        // A file can not be created inside another file.
+        let mut progress = Progress {
+            data: SaveData::new(),
+            path: Some(PathBuf::from("/dev/null/progress.toml")),
+            changed: false,
+            retry_at: None,
+        };
+        progress.attempt(1);
+
+        assert!(progress.save_if_changed().is_err());
+        assert!(progress.has_unsaved_changes());
+
+        // Not tried again right away.
+        assert!(progress.save_if_changed().is_ok());
+        assert!(progress.has_unsaved_changes());
+    }
+}
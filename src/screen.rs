@@ -1,50 +1,97 @@
-use std::io::{Stdout, Write};
-use crossterm::{cursor, queue, style, terminal};
-use crossterm::cursor::MoveTo;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags};
-use crossterm::style::{SetForegroundColor, Stylize};
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType};
-
-#[derive(Clone)]
+use crossterm::style;
+use crossterm::style::{Attribute, Attributes};
+use crate::backend::Backend;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Style {
+    pub foreground: style::Color,
+    pub background: style::Color,
+    pub attributes: Attributes,
+}
+
+impl Style {
+    pub fn new(foreground: style::Color) -> Style {
+        Style {
+            foreground,
+            background: style::Color::Reset,
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn background(mut self, background: style::Color) -> Style {
+        self.background = background;
+        self
+    }
+
+    pub fn attribute(mut self, attribute: Attribute) -> Style {
+        self.attributes.set(attribute);
+        self
+    }
+
+    pub fn bold(self) -> Style {
+        self.attribute(Attribute::Bold)
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style::new(style::Color::White)
+    }
+}
+
+impl From<style::Color> for Style {
+    fn from(color: style::Color) -> Self {
+        Style::new(color)
+    }
+}
+
+#[derive(Clone, PartialEq)]
 pub struct Cell {
     pub rune: char,
-    pub color: style::Color,
+    pub style: Style,
 }
 
 impl Cell {
-    fn new(c: char, color: style::Color) -> Cell {
-        Cell {rune: c, color }
+    pub fn new(c: char, style: Style) -> Cell {
+        Cell { rune: c, style }
     }
 
     fn clear(&mut self) {
-        self.set(' ', style::Color::White);
+        self.set(' ', Style::default());
     }
 
-    fn set(&mut self, c: char, color: style::Color) {
+    fn set(&mut self, c: char, style: Style) {
         self.rune = c;
-        self.color = color;
+        self.style = style;
     }
 }
 
 pub struct Screen {
-    stdout: Stdout,
+    backend: Box<dyn Backend>,
     width: u16,
     height: u16,
     real_width: u16,
     real_height: u16,
     buffer: Vec<Cell>,
+    front_buffer: Vec<Cell>,
+    force_redraw: bool,
+    /// Whether the terminal has been set up by `init` and not yet restored.
+    active: bool,
 }
 
 impl Screen {
-    pub fn new(stdout: Stdout, size: (u16, u16)) -> Screen {
+    pub fn new(backend: Box<dyn Backend>, size: (u16, u16)) -> Screen {
         let (width, height) = Screen::clamp_screen(size);
         let mut screen = Screen {
-            stdout,
+            backend,
             width,
             height,
             real_width: size.0,
             real_height: size.1,
             buffer: Vec::new(),
+            front_buffer: Vec::new(),
+            force_redraw: true,
+            active: false,
         };
 
         screen.resize(size);
@@ -64,30 +111,23 @@ impl Screen {
     }
 
     pub fn init(&mut self) -> Result<(), std::io::Error> {
-        enable_raw_mode()?;
-
-        queue!(
-            self.stdout,
-            terminal::EnterAlternateScreen,
-            cursor::Hide,
-            EnableMouseCapture,
-            Clear(ClearType::All),
-        )?;
+        self.active = true;
+        self.backend.init()?;
+        self.force_redraw = true;
         Ok(())
     }
 
+    /// Restores the terminal. A screen dropped without this restores the
+    /// terminal too, so leaving early because of an error never leaves it in
+    /// raw mode, but then errors are ignored.
     pub fn cleanup(&mut self) -> Result<(), std::io::Error> {
-        disable_raw_mode()?;
-
-        queue!(
-            self.stdout,
-            cursor::Show,
-            terminal::LeaveAlternateScreen,
-            DisableMouseCapture,
-        )?;
+        self.active = false;
+        self.backend.cleanup()
+    }
 
-        self.stdout.flush()?;
-        Ok(())
+    /// Whether key releases are reported, which is only known after `init`.
+    pub fn reports_key_releases(&self) -> bool {
+        self.backend.reports_key_releases()
     }
 
     pub fn clear(&mut self) {
@@ -103,13 +143,17 @@ impl Screen {
         self.real_width = size.0;
         self.real_height = size.1;
 
+        // The terminal may have reflowed or dropped its contents, so the front
+        // buffer can no longer be trusted.
+        self.force_redraw = true;
+
         if self.buffer.len() == (width * height) as usize {
             return;
         }
 
         self.buffer.clear();
         for _ in 0..(width * height) {
-            self.buffer.push(Cell::new(' ', style::Color::White));
+            self.buffer.push(Cell::new(' ', Style::default()));
         }
         self.clear();
     }
@@ -125,7 +169,7 @@ impl Screen {
         self.buffer[index].clone()
     }
 
-    pub fn set_cell_safe(&mut self, x: i16, y: i16, c: char, color: style::Color) {
+    pub fn set_cell_safe(&mut self, x: i16, y: i16, c: char, style: impl Into<Style>) {
         let width = self.width as i16;
         let height = self.height as i16;
         if x >= width || y >= height {
@@ -135,10 +179,10 @@ impl Screen {
             return;
         }
 
-        self.set_cell(x.clamp(0, width) as u16, y.clamp(0, height) as u16, c, color);
+        self.set_cell(x.clamp(0, width) as u16, y.clamp(0, height) as u16, c, style);
     }
     
-    pub fn set_cell(&mut self, x: u16, y: u16, c: char, color: style::Color) {
+    pub fn set_cell(&mut self, x: u16, y: u16, c: char, style: impl Into<Style>) {
         let index = self.xy_to_index(x, y);
 
         if x >= self.width || y >= self.height {
@@ -149,12 +193,13 @@ impl Screen {
             return;
         }
 
-        self.buffer[index].set(c, color);
+        self.buffer[index].set(c, style.into());
     }
 
-    pub fn draw_text(&mut self, x: u16, y: u16, text: &str, color: style::Color) {
+    pub fn draw_text(&mut self, x: u16, y: u16, text: &str, style: impl Into<Style>) {
+        let style = style.into();
         for (i, c) in text.chars().enumerate() {
-            self.set_cell(x + i as u16, y, c, color);
+            self.set_cell(x + i as u16, y, c, style);
         }
     }
 
@@ -168,16 +213,86 @@ impl Screen {
         (x, y)
     }
 
+    /// Flushes the back buffer to the terminal, only emitting the cells that
+    /// changed since the previous render. Adjacent changed cells are printed as
+    /// one run, and the cursor and style are only set when they differ from the
+    /// terminal's current state.
     pub fn render(&mut self) {
-        for (i, cell) in self.buffer.iter().enumerate() {
+        if self.force_redraw {
+            self.backend.clear().unwrap();
+        }
+
+        let mut run = String::new();
+        let mut cursor: Option<(u16, u16)> = None;
+        let mut current: Option<Style> = None;
+
+        for i in 0..self.buffer.len() {
+            let cell = &self.buffer[i];
+            let unchanged = !self.force_redraw
+                && self.front_buffer.get(i).is_some_and(|front| front == cell);
+
+            if unchanged {
+                continue;
+            }
+
             let (x, y) = self.index_to_xy(i);
-            queue!(
-                self.stdout,
-                MoveTo(x, y),
-                SetForegroundColor(cell.color),
-                style::PrintStyledContent(cell.rune.stylize())
-            ).unwrap();
+
+            if cursor != Some((x, y)) {
+                Screen::flush_run(self.backend.as_mut(), &mut run);
+                self.backend.move_to(x, y).unwrap();
+            }
+
+            if current != Some(cell.style) {
+                Screen::flush_run(self.backend.as_mut(), &mut run);
+                Screen::set_style(self.backend.as_mut(), current, cell.style);
+                current = Some(cell.style);
+            }
+
+            run.push(cell.rune);
+            cursor = Some((x + 1, y));
         }
-        self.stdout.flush().unwrap();
+
+        Screen::flush_run(self.backend.as_mut(), &mut run);
+        self.backend.flush().unwrap();
+
+        self.front_buffer.clone_from(&self.buffer);
+        self.force_redraw = false;
     }
-}
\ No newline at end of file
+
+    fn set_style(backend: &mut dyn Backend, current: Option<Style>, style: Style) {
+        match current {
+            Some(current) if current.attributes == style.attributes => {
+                if current.foreground != style.foreground {
+                    backend.set_foreground(style.foreground).unwrap();
+                }
+                if current.background != style.background {
+                    backend.set_background(style.background).unwrap();
+                }
+            }
+            _ => {
+                // Attributes can only be cleared all at once, and doing so also
+                // resets the colors, so everything has to be set again.
+                backend.set_attributes(style.attributes).unwrap();
+                backend.set_foreground(style.foreground).unwrap();
+                backend.set_background(style.background).unwrap();
+            }
+        }
+    }
+
+    fn flush_run(backend: &mut dyn Backend, run: &mut String) {
+        if run.is_empty() {
+            return;
+        }
+
+        backend.print(run).unwrap();
+        run.clear();
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.cleanup();
+        }
+    }
+}
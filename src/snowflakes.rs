@@ -1,4 +1,5 @@
 use crate::screen::Screen;
+use rand::Rng;
 use crossterm::style;
 
 const MAX_SNOW_FLAKES: usize = 500;
@@ -12,22 +13,22 @@ pub struct Snowflake {
     sprite: char,
 }
 
-pub fn create(width: u16, height: u16) -> Vec<Snowflake> {
+pub fn create(rng: &mut impl Rng, width: u16, height: u16) -> Vec<Snowflake> {
     let mut snow_flakes: Vec<Snowflake> = Vec::new();
     for _ in 0..100 {
         snow_flakes.push(Snowflake {
-            x: (width as f64 * rand::random::<f64>()).floor(),
-            y: ((height - 1) as f64 * rand::random::<f64>()).floor(),
-            speed: (rand::random::<f64>() * 1.0) + 0.5,
+            x: (width as f64 * rng.random::<f64>()).floor(),
+            y: ((height - 1) as f64 * rng.random::<f64>()).floor(),
+            speed: (rng.random::<f64>() * 1.0) + 0.5,
             sprite: SNOW_FLAKE_SPRITES
-                [(rand::random::<u16>() % SNOW_FLAKE_SPRITES.len() as u16) as usize],
+                [(rng.random::<u16>() % SNOW_FLAKE_SPRITES.len() as u16) as usize],
         });
     }
 
     snow_flakes
 }
 
-pub fn update(snow_flakes: &mut Vec<Snowflake>, width: u16, height: u16, phase: f64, dt: f64) {
+pub fn update(rng: &mut impl Rng, snow_flakes: &mut Vec<Snowflake>, width: u16, height: u16, phase: f64, dt: f64) {
     prune_snow_flakes(snow_flakes);
 
     for snow_flake in snow_flakes.iter_mut() {
@@ -38,7 +39,7 @@ pub fn update(snow_flakes: &mut Vec<Snowflake>, width: u16, height: u16, phase:
 
         if snow_flake.y as u16 >= ground_level {
             snow_flake.y = 0.0;
-            snow_flake.x = (width - 1) as f64 * rand::random::<f64>();
+            snow_flake.x = (width - 1) as f64 * rng.random::<f64>();
         }
 
         snow_flake.x = snow_flake.x.clamp(0.0, (width - 1) as f64);
@@ -51,15 +52,15 @@ fn prune_snow_flakes(snow_flakes: &mut Vec<Snowflake>) {
     }
 }
 
-pub fn spawn_mouse_snow_flakes(snow_flakes: &mut Vec<Snowflake>, mouse_position: (u16, u16)) {
-    if rand::random::<f64>() < SNOW_FLAKE_SPAWN_PROBABILITY {
+pub fn spawn_mouse_snow_flakes(rng: &mut impl Rng, snow_flakes: &mut Vec<Snowflake>, mouse_position: (u16, u16)) {
+    if rng.random::<f64>() < SNOW_FLAKE_SPAWN_PROBABILITY {
         const SPAWN_SPREAD: f64 = 6.0;
         snow_flakes.push(Snowflake {
-            x: mouse_position.0 as f64 + (rand::random::<f64>() * SPAWN_SPREAD),
-            y: mouse_position.1 as f64 + (rand::random::<f64>() * SPAWN_SPREAD) - 1.0,
-            speed: (rand::random::<f64>() * 0.5) + 2.8,
+            x: mouse_position.0 as f64 + (rng.random::<f64>() * SPAWN_SPREAD),
+            y: mouse_position.1 as f64 + (rng.random::<f64>() * SPAWN_SPREAD) - 1.0,
+            speed: (rng.random::<f64>() * 0.5) + 2.8,
             sprite: SNOW_FLAKE_SPRITES
-                [(rand::random::<u16>() % SNOW_FLAKE_SPRITES.len() as u16) as usize],
+                [(rng.random::<u16>() % SNOW_FLAKE_SPRITES.len() as u16) as usize],
         });
     }
 }
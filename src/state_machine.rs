@@ -1,40 +1,396 @@
+use crate::backend::ScriptedEvents;
+use crate::context::Context;
 use crate::input::Input;
-use crate::screen::Screen;
+use crate::screen::Cell;
+
+/// What a state wants done with the stack of states after its update.
+pub enum StateChange {
+    /// Puts a state on top, like a pause menu or a dialog. The current state
+    /// stays underneath, and continues where it left off when the new state
+    /// is popped.
+    Push(Box<dyn State>),
+    /// Removes the current state, returning to the one below it.
+    Pop,
+    /// Swaps the current state for another one.
+    Replace(Box<dyn State>),
+    /// Removes every state, and starts over with the given one.
+    Reset(Box<dyn State>),
+}
+
+/// What happens to the states below a state while it is on top of them.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Underlay {
+    /// Not drawn at all. The state covers the whole screen.
+    Hidden,
+    /// Drawn as they looked when the state was pushed, but not updated.
+    Frozen,
+    /// Updated and drawn as usual, but without any input.
+    Running,
+}
 
 pub trait State {
-    fn enter(&mut self, screen: &mut Screen, input: &mut Input);
-    fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) -> Option<Box<dyn State>>;
-    fn exit(&mut self, screen: &mut Screen, input: &mut Input);
+    fn enter(&mut self, ctx: &mut Context);
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange>;
+    /// Called zero or more times per frame, always with the same `dt`, before
+    /// `update`. Simulations that should not depend on the frame rate go here.
+    fn fixed_update(&mut self, _ctx: &mut Context, _dt: f64) {}
+    fn exit(&mut self, ctx: &mut Context);
+
+    /// What happens to the states below this one. States that only draw over
+    /// part of the screen, like dialogs, should not hide what is under them.
+    fn underlay(&self) -> Underlay {
+        Underlay::Hidden
+    }
+
+    /// Called on every state in the stack when the screen changes size, with
+    /// the new size of the screen.
+    fn resize(&mut self, _ctx: &mut Context, _width: u16, _height: u16) {}
+
+    /// Called on every state in the stack when the content files have been
+    /// read again, with the new content in the context. States that keep
+    /// content of their own should pick up the new version here.
+    fn reload(&mut self, _ctx: &mut Context) {}
+
+    /// Called on the top state when the terminal loses focus, for instance to
+    /// pause a game that is running on time.
+    fn focus_lost(&mut self, _ctx: &mut Context) -> Option<StateChange> {
+        None
+    }
+
+    /// Called on the top state when the terminal gets focus back.
+    fn focus_gained(&mut self, _ctx: &mut Context) -> Option<StateChange> {
+        None
+    }
+
+    /// Called when another state is pushed on top of this one.
+    fn pause(&mut self, _ctx: &mut Context) {}
+
+    /// Called when this state is on top again, after the state above it was
+    /// popped.
+    fn resume(&mut self, _ctx: &mut Context) {}
 }
 
+struct Layer {
+    state: Box<dyn State>,
+    /// The screen as it looked when the state was pushed, drawn under it if
+    /// it freezes the states below.
+    frozen_cells: Vec<Cell>,
+}
+
+/// A stack of states. Only the top state receives input, the ones below it
+/// are drawn and updated according to the `underlay` of the states above.
 pub struct StateMachine {
-    current_state: Option<Box<dyn State>>,
+    stack: Vec<Layer>,
+    /// Handed to the states below the top, so they never react to input
+    /// meant for the state on top of them.
+    idle_input: Input,
 }
 
 impl StateMachine {
     pub fn new() -> StateMachine {
         StateMachine {
-            current_state: None,
+            stack: Vec::new(),
+            idle_input: Input::with_source(Box::new(ScriptedEvents::new())),
         }
     }
-    
-    pub fn change(&mut self, screen: &mut Screen, input: &mut Input, new_state: Option<Box<dyn State>>) {
-        if let Some(ref mut state) = self.current_state {
-            state.exit(screen, input);
+
+    /// Whether the last state has been popped.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub fn push(&mut self, ctx: &mut Context, state: Box<dyn State>) {
+        if let Some(layer) = self.stack.last_mut() {
+            layer.state.pause(ctx);
         }
+        self.add(ctx, state);
+    }
 
-        self.current_state = new_state;
+    pub fn pop(&mut self, ctx: &mut Context) {
+        self.remove(ctx);
+        if let Some(layer) = self.stack.last_mut() {
+            layer.state.resume(ctx);
+        }
+    }
+
+    pub fn replace(&mut self, ctx: &mut Context, state: Box<dyn State>) {
+        self.remove(ctx);
+        self.add(ctx, state);
+    }
 
-        if let Some(ref mut state) = self.current_state {
-            state.enter(screen, input);
+    pub fn reset(&mut self, ctx: &mut Context, state: Box<dyn State>) {
+        while !self.stack.is_empty() {
+            self.remove(ctx);
         }
+        self.add(ctx, state);
+    }
+
+    fn add(&mut self, ctx: &mut Context, mut state: Box<dyn State>) {
+        let frozen_cells = match state.underlay() {
+            Underlay::Frozen => ctx.screen.clone_buffer(),
+            _ => Vec::new(),
+        };
+
+        state.enter(ctx);
+        self.stack.push(Layer { state, frozen_cells });
     }
 
-    pub fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) {
-        if let Some(ref mut state) = self.current_state {
-            if let Some(new_state) = state.update(screen, input, dt) {
-                self.change(screen, input, Some(new_state));
+    fn remove(&mut self, ctx: &mut Context) {
+        if let Some(mut layer) = self.stack.pop() {
+            layer.state.exit(ctx);
+        }
+    }
+
+    pub fn apply(&mut self, ctx: &mut Context, change: StateChange) {
+        match change {
+            StateChange::Push(state) => self.push(ctx, state),
+            StateChange::Pop => self.pop(ctx),
+            StateChange::Replace(state) => self.replace(ctx, state),
+            StateChange::Reset(state) => self.reset(ctx, state),
+        }
+    }
+
+    /// Tells every state that the screen has been resized. What the frozen
+    /// states looked like no longer fits the screen, so they are drawn blank
+    /// from now on.
+    pub fn resize(&mut self, ctx: &mut Context) {
+        let (width, height) = (ctx.screen.width(), ctx.screen.height());
+        for layer in &mut self.stack {
+            layer.frozen_cells.clear();
+            layer.state.resize(ctx, width, height);
+        }
+    }
+
+    /// Tells every state that the content has been reloaded.
+    pub fn reload(&mut self, ctx: &mut Context) {
+        for layer in &mut self.stack {
+            layer.state.reload(ctx);
+        }
+    }
+
+    pub fn focus_lost(&mut self, ctx: &mut Context) {
+        if let Some(change) = self.stack.last_mut().and_then(|layer| layer.state.focus_lost(ctx)) {
+            self.apply(ctx, change);
+        }
+    }
+
+    pub fn focus_gained(&mut self, ctx: &mut Context) {
+        if let Some(change) = self.stack.last_mut().and_then(|layer| layer.state.focus_gained(ctx)) {
+            self.apply(ctx, change);
+        }
+    }
+
+    /// The lowest state that is still updated, and whether the frozen screen
+    /// of that state should be drawn first.
+    fn lowest_running(&self) -> (usize, bool) {
+        let mut lowest = self.stack.len().saturating_sub(1);
+        loop {
+            match self.stack.get(lowest).map(|layer| layer.state.underlay()) {
+                Some(Underlay::Running) if lowest > 0 => lowest -= 1,
+                Some(Underlay::Frozen) => return (lowest, true),
+                _ => return (lowest, false),
             }
         }
     }
-}
\ No newline at end of file
+
+    pub fn update(&mut self, ctx: &mut Context, dt: f64) {
+        let Some(top) = self.stack.len().checked_sub(1) else {
+            return;
+        };
+        let (lowest, frozen) = self.lowest_running();
+
+        if frozen {
+            let cells = &self.stack[lowest].frozen_cells;
+            if cells.len() == ctx.screen.width() as usize * ctx.screen.height() as usize {
+                for (i, cell) in cells.iter().enumerate() {
+                    let (x, y) = ctx.screen.index_to_xy(i);
+                    ctx.screen.set_cell(x, y, cell.rune, cell.style);
+                }
+            }
+        }
+
+        // Changes requested by the states below the top are ignored, only
+        // the top state decides what happens next.
+        std::mem::swap(&mut ctx.input, &mut self.idle_input);
+        for layer in &mut self.stack[lowest..top] {
+            layer.state.update(ctx, dt);
+        }
+        std::mem::swap(&mut ctx.input, &mut self.idle_input);
+
+        if let Some(change) = self.stack[top].state.update(ctx, dt) {
+            self.apply(ctx, change);
+        }
+    }
+
+    pub fn fixed_update(&mut self, ctx: &mut Context, dt: f64) {
+        let Some(top) = self.stack.len().checked_sub(1) else {
+            return;
+        };
+        let (lowest, _) = self.lowest_running();
+
+        std::mem::swap(&mut ctx.input, &mut self.idle_input);
+        for layer in &mut self.stack[lowest..top] {
+            layer.state.fixed_update(ctx, dt);
+        }
+        std::mem::swap(&mut ctx.input, &mut self.idle_input);
+
+        self.stack[top].state.fixed_update(ctx, dt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use crossterm::style::Color;
+    use crate::context::testing::Harness;
+    use super::*;
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    /// Draws its letter in the top left corner, and logs what happens to it.
+    struct Logged {
+        letter: char,
+        underlay: Underlay,
+        log: Log,
+        /// Returned from the next update.
+        change: Option<StateChange>,
+    }
+
+    impl Logged {
+        fn new(letter: char, underlay: Underlay, log: &Log) -> Box<Logged> {
+            Box::new(Logged { letter, underlay, log: log.clone(), change: None })
+        }
+
+        fn log(&self, what: &str) {
+            self.log.borrow_mut().push(format!("{} {}", self.letter, what));
+        }
+    }
+
+    impl State for Logged {
+        fn enter(&mut self, _ctx: &mut Context) {
+            self.log("enter");
+        }
+
+        fn update(&mut self, ctx: &mut Context, _dt: f64) -> Option<StateChange> {
+            if ctx.input.is_key_pressed(' ') {
+                self.log("update with space");
+            } else {
+                self.log("update");
+            }
+            ctx.screen.set_cell(0, 0, self.letter, Color::White);
+            self.change.take()
+        }
+
+        fn exit(&mut self, _ctx: &mut Context) {
+            self.log("exit");
+        }
+
+        fn underlay(&self) -> Underlay {
+            self.underlay
+        }
+
+        fn pause(&mut self, _ctx: &mut Context) {
+            self.log("pause");
+        }
+
+        fn resume(&mut self, _ctx: &mut Context) {
+            self.log("resume");
+        }
+    }
+
+    fn frame(harness: &mut Harness, state_machine: &mut StateMachine) {
+        harness.ctx.input.update(1.0 / 60.0).unwrap();
+        harness.ctx.screen.clear();
+        state_machine.update(&mut harness.ctx, 1.0 / 60.0);
+        harness.ctx.screen.render();
+    }
+
+    #[test]
+    fn pushed_state_freezes_the_one_below() {
+        let mut harness = Harness::new((81, 40));
+        let log = Log::default();
+        let mut state_machine = StateMachine::new();
+
+        state_machine.push(&mut harness.ctx, Logged::new('a', Underlay::Hidden, &log));
+        frame(&mut harness, &mut state_machine);
+
+        let mut overlay = Logged::new('b', Underlay::Frozen, &log);
+        overlay.change = Some(StateChange::Pop);
+        state_machine.push(&mut harness.ctx, overlay);
+        frame(&mut harness, &mut state_machine);
+        frame(&mut harness, &mut state_machine);
+
+        assert_eq!(*log.borrow(), [
+            "a enter", "a update",
+            "a pause", "b enter", "b update", "b exit", "a resume",
+            "a update",
+        ]);
+        assert_eq!(harness.terminal.borrow().line(0).chars().next(), Some('a'));
+    }
+
+    #[test]
+    fn frozen_screen_is_drawn_under_the_top_state() {
+        let mut harness = Harness::new((81, 40));
+        let log = Log::default();
+        let mut state_machine = StateMachine::new();
+
+        state_machine.push(&mut harness.ctx, Logged::new('a', Underlay::Hidden, &log));
+        frame(&mut harness, &mut state_machine);
+        harness.ctx.screen.draw_text(5, 5, "frosset", Color::White);
+        state_machine.push(&mut harness.ctx, Logged::new('b', Underlay::Frozen, &log));
+        frame(&mut harness, &mut state_machine);
+
+        let terminal = harness.terminal.borrow();
+        assert_eq!(terminal.line(0).chars().next(), Some('b'));
+        assert_eq!(terminal.line(5).trim(), "frosset");
+    }
+
+    #[test]
+    fn changes_from_the_top_state_are_applied() {
+        let mut harness = Harness::new((81, 40));
+        let log = Log::default();
+        let mut state_machine = StateMachine::new();
+
+        state_machine.push(&mut harness.ctx, Logged::new('a', Underlay::Hidden, &log));
+        let mut replacement = Logged::new('c', Underlay::Hidden, &log);
+        replacement.change = Some(StateChange::Reset(Logged::new('d', Underlay::Hidden, &log)));
+        state_machine.apply(&mut harness.ctx, StateChange::Replace(replacement));
+
+        harness.send(Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE)));
+        frame(&mut harness, &mut state_machine);
+        frame(&mut harness, &mut state_machine);
+
+        assert_eq!(*log.borrow(), [
+            "a enter", "a exit", "c enter",
+            "c update with space", "c exit", "d enter",
+            "d update",
+        ]);
+        assert!(!state_machine.is_empty());
+    }
+
+    #[test]
+    fn running_underlay_updates_the_states_below_without_input() {
+        let mut harness = Harness::new((81, 40));
+        let log = Log::default();
+        let mut state_machine = StateMachine::new();
+
+        let mut below = Logged::new('a', Underlay::Hidden, &log);
+        below.change = Some(StateChange::Pop);
+        state_machine.push(&mut harness.ctx, below);
+        state_machine.push(&mut harness.ctx, Logged::new('b', Underlay::Running, &log));
+
+        harness.send(Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE)));
+        frame(&mut harness, &mut state_machine);
+        frame(&mut harness, &mut state_machine);
+
+        // The pop asked for by the state below is ignored.
+        assert_eq!(*log.borrow(), [
+            "a enter", "a pause", "b enter",
+            "a update", "b update with space",
+            "a update", "b update",
+        ]);
+        assert_eq!(harness.terminal.borrow().line(0).chars().next(), Some('b'));
+    }
+}
@@ -1,14 +1,30 @@
+use crate::context::Context;
 use crossterm::style;
-use crate::drawing::{draw_ascii, draw_ascii_safe, draw_text_box};
-use crate::input::{Input, MouseButton};
-use crate::screen::Screen;
-use crate::state_machine::State;
+use crate::drawing::{draw_ascii_safe, draw_text_box};
+use crate::actions::Action;
+use crate::input::MouseButton;
+use crate::state_machine::{State, StateChange};
+use crate::states::{DayInfo, DayKind};
+use crate::save::Outcome;
 use crate::states::main_state::MainState;
+use crate::states::pause_state::PauseState;
 use crate::states::transition_state::TransitionState;
 
+pub const DAY: DayInfo = DayInfo {
+    door: 14,
+    title: "day14.title",
+    author: "Faggruppe Rust",
+    kind: DayKind::Game,
+    unlocks_on: 14,
+    create: |_| Box::new(Day14State::new()),
+};
+
 pub struct Day14State {
     found_it: bool,
     timer: f64,
+    /// Whether another state is on top, so the time does not count even if
+    /// this state is still updated underneath it.
+    paused: bool,
 }
 
 impl Day14State {
@@ -16,21 +32,24 @@ impl Day14State {
         Day14State {
             found_it: false,
             timer: 0.0,
+            paused: false,
         }
     }
 }
 
 impl State for Day14State {
-    fn enter(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn enter(&mut self, _ctx: &mut Context) {
     }
 
-    fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) -> Option<Box<dyn State>> {
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, progress, locale, art, .. } = ctx;
 
         let (mouse_x, mouse_y) = input.mouse_position();
 
-        let center_x = screen.width() / 2;
-        let center_y = screen.height() / 2;
-        draw_ascii(screen, NORWAY, center_x - NORWAY_WIDTH / 2, center_y - NORWAY_HEIGHT / 2);
+        let norway = &art["norway"];
+        let center_x = screen.width() as i16 / 2;
+        let center_y = screen.height() as i16 / 2;
+        norway.draw(screen, "center", center_x, center_y);
 
         let mx = mouse_x as i16;
         let my = mouse_y as i16;
@@ -40,18 +59,21 @@ impl State for Day14State {
         draw_ascii_safe(screen, "|", mx+0, my+1);
 
         if !self.found_it {
-            self.timer += dt;
-            let question = "  Finn Hamar på kartet  ";
+            if !self.paused {
+                self.timer += dt;
+            }
+            let question = format!("  {}  ", locale.text("day14.task"));
             draw_text_box(screen, screen.width(), screen.height(), &question, -20, -15, (0, 0), false);
 
             if input.is_mouse_down(MouseButton::Left) {
-                let hit = screen.get_cell(mouse_x, mouse_y);
-                if hit.rune == HIT_CHAR {
+                let origin = norway.origin("center", center_x, center_y);
+                if norway.hotspot_contains("hamar", origin, (mouse_x, mouse_y)) {
                     self.found_it = true;
+                    progress.complete(DAY.door, Outcome::Time(self.timer));
                 }
             }
         } else {
-            let question = format!("  Du fant Hamar på {:.2} sekunder!  ", self.timer);
+            let question = format!("  {}  ", locale.format("day14.done", &[("time", &format!("{:.2}", self.timer))]));
             draw_text_box(screen, screen.width(), screen.height(), &question, -20, -15, (0, 0), false);
         }
 
@@ -59,64 +81,114 @@ impl State for Day14State {
             screen,
             screen.width(),
             screen.height(),
-            "Tilbake",
+            &locale.text("common.back"),
             -20,
             -12,
             input.mouse_position(),
             input.is_mouse_up(MouseButton::Left),
         );
-        if exit && input.is_mouse_up(MouseButton::Left) {
-            return Some(Box::new(TransitionState::new(Box::new(MainState::new()), None)));
+        if (exit && input.is_mouse_up(MouseButton::Left)) || input.is_action_pressed(Action::Back) {
+            return Some(StateChange::Replace(Box::new(TransitionState::new(Box::new(MainState::new()), None))));
+        }
+
+        // Pausing after drawing, so the map stays visible under the pause.
+        if !self.found_it && input.is_action_pressed(Action::Pause) {
+            return Some(StateChange::Push(Box::new(PauseState::new())));
         }
 
         None
     }
 
-    fn exit(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn exit(&mut self, _ctx: &mut Context) {
+    }
+
+    fn focus_lost(&mut self, _ctx: &mut Context) -> Option<StateChange> {
+        if !self.found_it {
+            return Some(StateChange::Push(Box::new(PauseState::new())));
+        }
+        None
+    }
+
+    fn pause(&mut self, _ctx: &mut Context) {
+        self.paused = true;
+    }
+
+    fn resume(&mut self, _ctx: &mut Context) {
+        self.paused = false;
     }
 }
 
-const NORWAY_WIDTH: u16 = 75;
-const NORWAY_HEIGHT: u16 = 36;
-
-const HIT_CHAR: char = '⠀';
-
-const NORWAY: &str = r#"
-                                                         ._,----._
-                                                       Hammerfest   ~-_
-                                                     _/                >
-                                                 __--             ___-~
-                                                /            _,-_ `---_,
-                                          _--\ /            /    `--\ /
-                                         /    *            |        ,'
-                                     /'\ \  TROMS0        (        (
-             Atlantic ocean        /'   \/      _/\___    /        /
-                                 /'_,-Narvik___/ \_   `\/         |
-             Norwegian Sea     /'/'  _/  . /       ~~\             \_
-                              /`'   /    _/           |              \
-                                   /.  _/              \              |
-                                 _/ Bodoe               )            (
-                               _/    /                  \             |
-                              /     /                   /             |
-                            _/     /                    \             \
-                           /      |                 ,----+-.           (
-                          /      /                .'        )           \_
-                        _/      |                 |         \            (
-                       /       /                  |        /~
-                     _/       |                   >       / FINLAND
-                  __/         _)                 /       /
-              ___/    .    /~~  SWEDEN          /      /
-           __/  Trondheim/                    _/      /
-         ,/             <                    /      .'
-        /                |                  /       |
-       |      NORWAY     |                 /        |
-       \____             |                /         |
-       ,----'        .   /               (          |               ___---
-       |       Lillehammer                \          \        __,--~~
-       |.             ⠀  \                 \      _   ~-_  _*~Helsinki
-      Bergen        Oslo /                  \    <_>     ~---~~
-       |             *  |                    >                      ______
-       `\           | | |         Stockholm*/                _-*~~~~
-        `.         /   \|                 /~              <><
-      Stavanger ,-'     \               _/     __          <__>\   ESTONIA
-          `\___/         .Gothenburg   /      < /               ~-,_______"#;
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+    use crate::context::testing::Harness;
+    use crate::state_machine::StateMachine;
+    use super::*;
+
+    fn click(harness: &Harness, kind: MouseEventKind, (column, row): (i16, i16)) {
+        harness.send(Event::Mouse(MouseEvent {
+            kind,
+            column: column as u16,
+            row: row as u16,
+            modifiers: KeyModifiers::NONE,
+        }));
+    }
+
+    fn frame(harness: &mut Harness, state_machine: &mut StateMachine) {
+        harness.ctx.input.update(1.0 / 60.0).unwrap();
+        harness.ctx.screen.clear();
+        state_machine.update(&mut harness.ctx, 1.0 / 60.0);
+        harness.ctx.screen.render();
+    }
+
+    #[test]
+    fn clicking_hamar_completes_the_day() {
+        let mut harness = Harness::new((121, 40));
+        let mut state_machine = StateMachine::new();
+        state_machine.push(&mut harness.ctx, Box::new(Day14State::new()));
+
+        let screen = &harness.ctx.screen;
+        let (x, y) = harness.ctx.art["norway"].origin("center", screen.width() as i16 / 2, screen.height() as i16 / 2);
+
+        click(&harness, MouseEventKind::Down(MouseButton::Left), (x, y));
+        frame(&mut harness, &mut state_machine);
+        assert!(harness.ctx.progress.day(DAY.door).is_none_or(|day| !day.is_completed()));
+
+        click(&harness, MouseEventKind::Up(MouseButton::Left), (x, y));
+        click(&harness, MouseEventKind::Down(MouseButton::Left), (x + 22, y + 31));
+        frame(&mut harness, &mut state_machine);
+        assert!(harness.ctx.progress.day(DAY.door).is_some_and(|day| day.is_completed()));
+
+        frame(&mut harness, &mut state_machine);
+        let done = harness.ctx.locale.format("day14.done", &[("time", &"0.03")]);
+        assert!(harness.terminal.borrow().contents().contains(&done));
+    }
+
+    #[test]
+    fn time_stops_while_paused() {
+        let mut harness = Harness::new((121, 40));
+        let mut state_machine = StateMachine::new();
+        state_machine.push(&mut harness.ctx, Box::new(Day14State::new()));
+        frame(&mut harness, &mut state_machine);
+
+        harness.send(Event::FocusLost);
+        harness.ctx.input.update(1.0 / 60.0).unwrap();
+        state_machine.focus_lost(&mut harness.ctx);
+        for _ in 0..60 {
+            frame(&mut harness, &mut state_machine);
+        }
+
+        let resume = harness.ctx.locale.text("pause.resume");
+        assert!(harness.terminal.borrow().contents().contains(&resume));
+
+        harness.send(Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)));
+        frame(&mut harness, &mut state_machine);
+        let screen = &harness.ctx.screen;
+        let (x, y) = harness.ctx.art["norway"].origin("center", screen.width() as i16 / 2, screen.height() as i16 / 2);
+        click(&harness, MouseEventKind::Down(MouseButton::Left), (x + 22, y + 31));
+        frame(&mut harness, &mut state_machine);
+
+        let best_time = harness.ctx.progress.day(DAY.door).and_then(|day| day.best_time).unwrap();
+        assert!(best_time < 0.1, "{}", best_time);
+    }
+}
@@ -1,116 +1,59 @@
-use crate::input::{Input, MouseButton};
-use crate::screen::Screen;
-use crate::state_machine::State;
-use rand::{thread_rng, Rng};
-use crate::drawing::{draw_ascii, draw_ascii_safe, draw_question, draw_text_box};
+use crate::context::Context;
+use crate::actions::Action;
+use crate::state_machine::{State, StateChange};
+use crate::states::{DayInfo, DayKind};
+use crate::save::Outcome;
+use crate::drawing::{draw_question, draw_text_box};
 use crate::input;
 use crate::states::main_state::MainState;
 use crate::states::transition_state::TransitionState;
 
-pub struct Day24State {
-    title_text: String,
-}
+pub const DAY: DayInfo = DayInfo {
+    door: 24,
+    title: "day24.title",
+    author: "Faggruppe Rust",
+    kind: DayKind::Picture,
+    unlocks_on: 24,
+    create: |_| Box::new(Day24State::new()),
+};
+
+pub struct Day24State;
 
 impl Day24State {
     pub fn new() -> Self {
-        let title_text = "Behold! Our Father Christmas!".to_string();
-        Day24State { title_text }
+        Day24State
     }
 }
 
 impl State for Day24State {
-    fn enter(&mut self, screen: &mut Screen, input: &mut Input) {  }
+    fn enter(&mut self, ctx: &mut Context) {
+        ctx.progress.complete(DAY.door, Outcome::Done);
+    }
 
-    fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) -> Option<Box<dyn State>> {
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, locale, art, .. } = ctx;
 
         let h = screen.height() as i16;
         let w = screen.width() as i16;
-        draw_ascii_safe(screen, FATHER_CHRISTMAS, w / 2 - WIDTH / 2, h / 2 - HEIGHT / 2);
+        art["father_christmas"].draw(screen, "center", w / 2, h / 2);
         draw_text_box(
             screen,
             screen.width(),
             screen.height(),
-            &self.title_text,
+            &locale.text("day24.behold"),
             0,
             10,
             (0, 0),
             false,
         );
 
-        if input.is_key_up('q') {
-            return Some(Box::new(TransitionState::new(Box::new(MainState::new()), None)));
+        if input.is_action_pressed(Action::Back) {
+            return Some(StateChange::Replace(Box::new(TransitionState::new(Box::new(MainState::new()), None))));
         }
 
         None
     }
 
-    fn exit(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn exit(&mut self, _ctx: &mut Context) {
     }
 }
-
-pub const WIDTH: i16 = 101;
-pub const HEIGHT: i16 = 60;
-
-pub const FATHER_CHRISTMAS: &str = r#"
-%*++++=++#+-+==+====-=*####**+=+**+#*#%%#+*##%@@%###****+###***##%%%%%#%%%*#####%%#**##%#*##*#*+=+**
-**+-===+=+##+#+-+++===***+********##**###++==+#%%%#*#%%#*+++*****#**%%%%##*=#%@@*+**##%%@@@%***%##*=
-+=+++**++*+=**#%+=++==*==++==+#****#+=++*##*##**+=+==+==-==::=*+*#%%%%%%%##*=+*##**#***#%@@@@%#%%%%%
-#+=-+*++*#@%##***+==+==++*+*+*+++**#####++++*--=-=+===+---------+***++*#%%#*##*++***+*%%**%%#***#%%%
-##*#**++++***#####*+++===+=*+****+########*--*===*=---=-----::----+*==+***####***=+++***%#%%%***##*#
--====-+**########*++++=++#***+##*++*#%#*#=-::=++++*#*+-=-:=--::-::-++**==***#**######%#####%#%##****
-+===++=+*#*###*+#++++####+*+**++++**+##:--+=++--*##%#*=*%#%**-:::--:=***#*###*##*##%%########%%#####
-+=+=***+=**%#%#==++**++=++###**#=+++++=:-=+**#*:-+***+**#%#@%#+-::--=-##%*+*#####****#%#*#%%@@%%%@%%
-*+*+++###*#####%%#####*+=#*++*=*==*=+==-+-##+--------======*#%#+-=+-=-+*+**#+##*####*+*##%%%@@%%@@##
-=+*+#+*#++***#*#%##%%%%*+++==-+*#*##=--+=-=---::::::::::::::-+##=+-=--=#@%%#*%%%@@@%#***#%%%%%%%*==-
-+*##**+*##**+*######%#@%#%#+***###*#===-*+-----::::::::::::::-=+#+++=-:++*+%@%%@@@@@%###%%%@%%%#=+*+
-+***++**##**#%##*+*++*+***%%###*#%+#*=+++==------::::::::::::::-+*+=---=++***++#%@@@@%%@@@%*+===*##*
-+=++===+*#=*-*+=#+=*+=*#####*+++*#***=+**+=--------:::::::::::::-+**+==#***=+*##@%@@@@@@@@%%+=+*+=+*
-++++=+=+*=*+#+**+==+*#*+***=++*#++#*#*###=--------=-------:::::::-=#*==*++**==+==+=++++**%%**%#*+*==
-+++#+**#+++*+**##**####***++**+*=*#=*+###@+=====***++=-=++++=-:-:#***-*#+=*#=+----==++===-=**##%####
-======+++*+**==++####*#####**###***+=****===*##%###*%::@+**##+#--==++=*###*#**+==-====*++=+++#%%#**#
-**=-++=+-===+*+**==***##****####*##****+*=---=++*+++-:::-=+*+=-::*---*##*++**#*##%=+++++==+**++*++**
-**+##++**==--=##**=*#+######*#####**#**++=-:*:----#=-:::-:--::::-:--**#=+*+++*#*+***+****#+=+#*#%%#*
-*++*++*+*+=+=+=*=+=+**#**##%%%####*####-+===------+---:::=---:::::-:+###%%#*#%%%#==+++######++#%####
-++++#*++*#######++=+=++*++*#%%##%%#####*+=+==---=++#%++#+=+=--------++#@%@@%%%%%%%***%%#****#==+%##*
-++=*#%%******##%%###*++=+++**#####%%####*++++==+=-=**+*=+=-+*==----##=+*+%@@#+=#@%#*#%@%##**#*@+*###
-#####%%%#****#%%%#####=##++*#*##**+#******=+++++=+*+#+*++==+++----#%##*+%@@#=-=+=**%%%%%@@%%####*%#*
-*###**%%####%%%%%%%%%%##**+=++*#***#*#**+*+++=+#%%%%%*#*++=#+----=###%%#*=-+*+####=+####%%%%#**###%#
-#####*+#####%%%@%#%%%%%%%#+##+++*###=++++##+++=+======-------==--=##%#%%%%#*+=+==-=+%#*%%########*##
-**#####*####%%%%%%%%#####*###+%#+%#%+++#*##%****===+++*+=--==+=-+==*%%##%%@**#+++=====%@%%%#*#%#####
-*%%#%**%*#####%%%%%@@%%%%%%%%@%%%%##**##****#%#**++====--=====-=+=-*-%####%@@%%+=+=+%+==-#%**###*##*
-@@@%%@%##%%##%%%%%###%%%%%%%%%%%%%%****#**++**@%%#***++==+**+==*++=%+=##***#%%@@%#=+*+*%%%#*+#%%#*#*
-%%%%@%####%###%%%%%#%##%%%#%%@@%%+***#***##++++@%%%%%%####*++=%*++-=++=###****#**#++=+==%####%%%####
-%%%%%%%%@@%#%#%##%%%%%%#%%%%%%*=+***+*%#*#%##++=+#%%%##**+===###*=-=#+==%%#%*#%***+++**+-==*#%%%%%%%
-@%%%%%%%%%%@@%%#@@@@@@@%%%=+*+*++****#*#**#%##%**==%%##*++==+%%+**++%#+=-*%%%%%*##*++=+#=#-+-+#%%@@@
-%#####%%%@%%@@@@@@@@@*=*=+++**++*****####*#####*#**-==*++=====-=+-*=*#*+=====-%%%####*+*##***=+###%%
-%%%######%%#%%@@@==+++**+**+**+*+++#*+#+*%++##%#+#%#*=-==*+==+==:---=%+++==+=+=--=##*#**#+@##++=+*=#
-%%%%%##%#%%%%===+=+++++*++*+*+++**+*****+*%##*#@#*#***+==*#=-=====-=+=+++*+-=-=======-****+##%#*=-=+
-%%#%%%##%#===++==+++=++++**++++*++*++#**+#**#*+*%@%*#+*#*##++=*=*==***=++=+=-+-+++=+====+##########+
-###%%%%%#+**+=**+*+=++**+***#+++**+++***+**++#***#%@+#+#*#*+-+++==+%+=++==+==*-++*+++=+===-#%*@%##**
-*###@%##+***==#**+++*+*+**+*=+**=+*+++****++**+###%%+#*=#%+*+=++++#*%+*+++=++-++=+*+*+=++-==%##**#*+
-@%#**#%%+**#+++#*=*+*+=**#*=+=+=**==++++++**+-%+%%@*%#%**#+*-+++***=+%=++=--====+-+*+==++++=####%@%%
-%##*#%@+*+#*##*+*#+******#*%%@%*%##@%+++**=#**+*+*@#%###***++=+*=*+=++-+*=====*#%%%+=++*+++=#%%%%%%#
-@%%%%@#+#*##*##*##***+*+*+#%%@@@@@@@@@@@%%+=+++*+++*##*%###=+##**+*=*=+*%@@%%###++*+*+++***==%%%%**#
-@@@@@%+*+**#*#*#**+***+#*#*#%%%%%@@@@@@@@@@@%#=*+*+*##%##%+*=#+++++#*%%%%%#%#***+==++=+**+*==%%%%##%
-@##@@+***++**#*#+#**++**+****#*#***%%%@@@@@@@@@*+=+*++#%%##+#*#++*%#%%#######+**++*+=+*=++++==%%%@%%
-%*%@++*#+####*##******#+++#+#*+++***####%%@@@@@@@%%=+**%%%%#**#*%#*****+*+=**#+++++**=*++***==%%@%%@
-**#*++*+*#*#%**##+**#***#++*+++**#*+*+**##%##%%@@@@%%=++%%###+%##*#*#++++**+++==+==-+=*=+#=++==%%@@@
-*##*++*##***#%####**++*+**#+#**+**+++*+*+++#*#%%%%%@@@%=+%++##%#*+****+=+++++=+++==*+++*+***+==%%@%%
-*#+***#*#*#**#####*#**#+*+##*++++##**+******+*****###%@@@*####***#*+**++=#+*++**+==++++***+====*%@%%
-#+#*###*+**#*#**#%%#*##**#+*#+##+**#*+*+*+***#*%#+**#*+%*****#+*+#*++**++%+++=**==+++++++*+++++=%%%%
-++*+++**+**+**###%##*#**##+*+++****+*#+**+++++=*#**#*+***=-**+***++*+#*=+#++*+++++-=++=++-++*+++%%%%
-**********###*+***#%##***##*#*###++*#*++***+****+***+*#%-%*=%%***#**+##*+*+-+++=+=+==++*+*++++=+=#%#
-###*%%##%*##*****#**##**#######***#**++******=**+*+***%##%##+++*=+**###++*=++**+=+**+=+=*++==+==+%#%
-***#***#***#+**#########*###*+##***++*+*+**#*++**+##+*%#==++*++*++++###++=*+++=*+*+**+++**+====+=#%%
-***#**++++++****#%###%###*#%*##**#*+*##=++*****++***####=+++*#=+***+#+#*+=*+*++**+*+++*+*#**+-=+==+#
-#*+**+*+++*+**##%#%%@%%%*%##%##*#*+*#+*+#***+**#*#+*####+++#=****++#**%*+++**++*+++*+++*@#%%*++====*
-**++**#*#*#*#*#%%%%@@@#%###%#**#*###*****+*#**#***+#**#%*+#+*+**=****#%=+=+***=++*#==+*@%%%%#**=+==#
-*+**##########*#%@@@@%####*##%*%*#*#*#+#*+####*#*#*****%=+****+++*#*#*%+++++#*+++*++=+@@@@%%#+++===*
-***#%%%%%%**###%%%@@@%%%####%##**+#####***###*+**##+*##%+*#**+**##+*###++**+****+++=+@@@@@%%#***+===
-##***#%####%*##%%@@@@%######+##**###*+*#*#**#*#***++*+#%*+*****+=#***#%+**+**++**==+=@@@@@#%##%#*#+=
-%##*##*#%##*##%@@@@@%%###%#**#*###+#*#*#%#**#*#*#+****#%+**+*+++*#**+#*+++**+++*+**++@@@@@%#*####*#+
-%%%%##=**%%###@@@@@%######*###%##########*#+#**#**#***#%++*+********##**=#**++**+++++@@@@%#**+++****
-**%%##*#*###%##@@@@@%#*##%#*##*#*#**#%###*##*#****#***#%+****#*+*#****%##*#+*****++*+@@@@@@%##=+++==
-+**#%#*##%%%%%%@@@@@%#####***####*##*##%******#*#*##*##*++***%*###*+*#+*#**+=*=**+*+=@@@@@%###*+++=+
-**++*****+#%%%@@%%%%%############**#####**#%+##+#**#**#***%+****+*+#+***##***==+**++*@@@@@@@@@@%#+**
-"#;
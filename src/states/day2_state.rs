@@ -1,12 +1,30 @@
+use crate::context::Context;
 use rand::prelude::IndexedRandom;
 use rand::Rng;
 use rand::seq::SliceRandom;
-use crate::drawing::{draw_ascii, draw_text_box};
+use crossterm::style::Color;
+use crate::drawing::{draw_text_box, draw_text_box_styled};
+use crate::actions::Action;
 use crate::input::{Input, MouseButton};
-use crate::screen::Screen;
-use crate::state_machine::State;
+use crate::locale::Locale;
+use crate::screen::{Screen, Style};
+use crate::state_machine::{State, StateChange};
+use crate::states::{DayInfo, DayKind};
+use crate::save::Outcome;
 use crate::states::main_state::MainState;
 use crate::states::transition_state::TransitionState;
+use crate::transition::CrossFade;
+
+const SELECTED_TILE: Color = Color::Rgb { r: 20, g: 90, b: 40 };
+
+pub const DAY: DayInfo = DayInfo {
+    door: 2,
+    title: "day2.title",
+    author: "Faggruppe Rust",
+    kind: DayKind::Puzzle,
+    unlocks_on: 2,
+    create: |ctx| Box::new(Day2State::new(&mut ctx.rng)),
+};
 
 struct Piece {
     x: u16,
@@ -30,9 +48,9 @@ pub struct Day2State {
 }
 
 impl Day2State {
-    pub fn new() -> Self {
+    pub fn new(rng: &mut impl Rng) -> Self {
         Day2State {
-            pieces: create_pieces(),
+            pieces: create_pieces(rng),
             selected: vec![],
             moves: 0,
             confetti: vec![],
@@ -40,12 +58,11 @@ impl Day2State {
     }
 }
 
-fn create_pieces() -> Vec<Piece> {
-    let mut rng = rand::thread_rng();
+fn create_pieces(rng: &mut impl Rng) -> Vec<Piece> {
     let mut pieces = vec![];
 
     let mut sprites = vec!['α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ', 'α', 'β', 'γ', 'δ', 'ε', 'ζ', 'η', 'θ'];
-    sprites.shuffle(&mut rng);
+    sprites.shuffle(rng);
 
     for x in 0..4 {
         for y in 0..4 {
@@ -57,17 +74,16 @@ fn create_pieces() -> Vec<Piece> {
     pieces
 }
 
-fn create_confetti(width: u16, height: u16) -> Vec<Particle> {
-    let mut rng = rand::thread_rng();
+fn create_confetti(rng: &mut impl Rng, width: u16, height: u16) -> Vec<Particle> {
     let mut confetti = vec![];
 
     for _ in 0..100 {
         let x = width as f64 / 2.0;
         let y = height as f64 / 2.0;
-        let speed = rng.gen_range(1.0..30.0);
-        let angle = rng.gen_range(0.0..std::f64::consts::PI * 2.0);
+        let speed = rng.random_range(1.0..30.0);
+        let angle = rng.random_range(0.0..std::f64::consts::PI * 2.0);
         let sprite = ['.', ',', '\'', '`', '^', '"', '*', 'o', 'O', '@']
-            .choose(&mut rng)
+            .choose(rng)
             .unwrap()
             .clone();
         confetti.push(Particle { x, y, speed, angle, sprite });
@@ -77,18 +93,16 @@ fn create_confetti(width: u16, height: u16) -> Vec<Particle> {
 }
 
 impl State for Day2State {
-    fn enter(&mut self, screen: &mut Screen, input: &mut Input) {
-        self.confetti = create_confetti(screen.width(), screen.height());
-    }
+    fn enter(&mut self, ctx: &mut Context) {
+        let Context { screen, rng, .. } = ctx;
 
-    fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) -> Option<Box<dyn State>> {
+        self.confetti = create_confetti(rng, screen.width(), screen.height());
+    }
 
-        if let Some((width, height)) = input.resized() {
-            self.confetti = create_confetti(width, height);
-        }
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, progress, locale, art, .. } = ctx;
 
-        let santa_y = (screen.height() as f64 / 2.0 - 20.0).clamp(0.0, screen.height() as f64 - 40.0) as u16;
-        draw_ascii(screen, SANTA, screen.width() - 50, santa_y);
+        art["santa_face"].draw(screen, "peek", screen.width() as i16 - 1, screen.height() as i16 / 2);
 
         let new_selected = draw_boxes(screen, input, &self.pieces, &self.selected);
         if new_selected.len() > 0 && input.is_mouse_up(MouseButton::Left) {
@@ -120,20 +134,24 @@ impl State for Day2State {
                     self.pieces.remove(second);
                 }
                 self.selected.clear();
+
+                if self.pieces.is_empty() {
+                    progress.complete(DAY.door, Outcome::Moves(self.moves));
+                }
             }
         }
 
         if self.pieces.len() == 0 {
-            draw_win(screen, dt, &mut self.confetti, self.moves);
+            draw_win(screen, locale, dt, &mut self.confetti, self.moves);
         }
 
-        let explanation1 = "Finn to like brikker og klikk på dem for å fjerne dem";
-        let explanation2 = "Målet er å fjerne alle brikkene";
+        let explanation1 = locale.text("day2.explanation");
+        let explanation2 = locale.text("day2.goal");
         draw_text_box(
             screen,
             screen.width(),
             screen.height(),
-            explanation1,
+            &explanation1,
             0,
             -16,
             (0, 0),
@@ -143,7 +161,7 @@ impl State for Day2State {
             screen,
             screen.width(),
             screen.height(),
-            explanation2,
+            &explanation2,
             0,
             -13,
             (0, 0),
@@ -154,20 +172,24 @@ impl State for Day2State {
             screen,
             screen.width(),
             screen.height(),
-            "Tilbake",
+            &locale.text("common.back"),
             0,
             12,
             input.mouse_position(),
             input.is_mouse_up(MouseButton::Left),
         );
-        if exit && input.is_mouse_up(MouseButton::Left) {
-            return Some(Box::new(TransitionState::new(Box::new(MainState::new()), None)));
+        if (exit && input.is_mouse_up(MouseButton::Left)) || input.is_action_pressed(Action::Back) {
+            return Some(StateChange::Replace(Box::new(TransitionState::new(Box::new(MainState::new()), None).effect(CrossFade::new()))));
         }
 
         None
     }
 
-    fn exit(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn exit(&mut self, _ctx: &mut Context) {
+    }
+
+    fn resize(&mut self, ctx: &mut Context, width: u16, height: u16) {
+        self.confetti = create_confetti(&mut ctx.rng, width, height);
     }
 }
 
@@ -185,21 +207,20 @@ fn draw_boxes(screen: &mut Screen, input: &mut Input, pieces: &Vec<Piece>, selec
         let x = piece.x as i16 * box_size + x_offset;
         let y = piece.y as i16 * box_height + y_offset;
 
-        let str = if selected.contains(&i) {
-            piece.sprite.to_string()
+        let (str, fill) = if selected.contains(&i) {
+            (piece.sprite.to_string(), Style::default().background(SELECTED_TILE).bold())
         } else {
-            "  ".to_string()
+            ("  ".to_string(), Style::default())
         };
 
-        let hovered = draw_text_box(
+        let hovered = draw_text_box_styled(
             screen,
-            screen.width(),
-            screen.height(),
             &str,
             x,
             y,
             input.mouse_position(),
             input.is_mouse_up(MouseButton::Left),
+            fill,
         );
 
         if hovered {
@@ -210,7 +231,7 @@ fn draw_boxes(screen: &mut Screen, input: &mut Input, pieces: &Vec<Piece>, selec
     new_selected
 }
 
-fn draw_win(screen: &mut Screen, dt: f64, confetti: &mut Vec<Particle>, moves: u32) {
+fn draw_win(screen: &mut Screen, locale: &Locale, dt: f64, confetti: &mut Vec<Particle>, moves: u32) {
     for particle in confetti.iter_mut() {
         particle.x += particle.speed * particle.angle.cos() * dt;
         particle.y += particle.speed * particle.angle.sin() * dt;
@@ -223,51 +244,14 @@ fn draw_win(screen: &mut Screen, dt: f64, confetti: &mut Vec<Particle>, moves: u
         screen.set_cell(particle.x as u16, particle.y as u16, particle.sprite, crossterm::style::Color::White);
     }
 
-    let str = format!("Gratulerer! Du klarte det på {} trekk!", moves);
-    draw_text_box(
+    let str = locale.plural("day2.done", moves as i64, &[]);
+    draw_text_box_styled(
         screen,
-        screen.width(),
-        screen.height(),
         &str,
         0,
         0,
         (0, 0),
         false,
+        Style::default().background(SELECTED_TILE).bold(),
     );
 }
-
-const SANTA: &str = r#"
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣀⣠⣤⣤⣶⣶⣶⣶⣶⣶⣦⣤⣄⡀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣠⡤⠴⠶⠶⠶⠶⣶⣤⣄⣀⣤⡶⠾⠿⠿⠟⠛⠛⠛⠛⠛⠛⠻⠿⠿⢿⣿⣿⣿⣷⣦⣀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⡤⠞⠋⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠈⠉⠛⠓⠒⠲⠤⣤⣄⡀⠀⠀⠀⠀⠀⠀⠀⠀⠉⠙⠻⣿⣿⣦⡀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⡰⠋⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠌⣉⣳⢦⡀⠀⠀⠀⠀⠀⠀⠀⠀⠈⠻⣿⣷⡄⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣴⠟⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠈⠙⠛⠲⢤⣄⠀⠀⠀⠀⠀⠀⠀⠙⣿⣷⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢠⠏⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠉⢳⣄⠀⠀⠀⢀⡀⠀⠹⣿⡇⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣾⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠙⣧⠀⠀⣿⠀⠀⠀⢻⣿⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢰⡟⠄⠀⠀⠀⢀⣠⠴⠒⠒⠛⠉⠉⠒⠒⠒⠒⠒⠤⣤⣀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢬⢧⡀⢿⣆⠀⠀⢸⣿⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠈⣧⠀⠀⣠⣞⣉⠀⠀⠀⠀⠀⠀⠀⠀⢀⡴⠖⠉⠉⠓⠺⣝⡶⣄⡀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠻⣧⡀⣹⣆⠀⠀⣿⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠻⣦⡞⠁⠀⠉⢳⡄⠀⠀⠀⠀⢸⡗⡞⠀⠀⠀⠀⠀⠀⠀⠙⣎⠻⣦⣀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠙⣷⡭⣿⠀⠀⣿⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢹⣇⢀⣠⠤⠾⢧⡀⠀⠀⠀⠈⢷⣷⠤⠴⠶⢶⣦⣄⠀⠀⠘⡆⠈⠻⣦⡀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢹⣿⣸⡇⠀⡿⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠛⡏⢀⣤⣤⣤⣷⠄⠀⠀⠀⠀⠀⠀⣠⣤⣤⣀⡉⠳⢄⠀⢹⠀⠀⢹⢿⣄⠀⠀⠀⠀⠀⠀⠀⠀⢀⠀⣿⣿⠁⢀⡇⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⡀⠙⠋⣛⣿⠿⠯⣤⡀⠀⠀⠀⠀⠀⠁⠘⠛⠛⠽⣄⠈⠛⠸⠂⠀⢸⠀⠹⣦⠴⠲⣶⡀⠀⠀⠀⠘⣆⣿⡟⠀⢸⠁⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣀⡀⡞⠁⢠⡞⠁⠀⠀⠀⠀⠀⠀⣤⣀⠀⠀⠀⠀⠀⠀⠀⠈⠁⠀⠀⠀⢀⡏⠀⢰⣏⡧⠀⠘⣷⠀⠀⠀⠀⢹⡿⠀⡀⣸⠀⠀⠀⠠⢤⣀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣴⠋⠁⢰⡇⠀⢸⣧⣄⣀⣀⡀⠀⠀⠀⠀⠈⢳⡀⠀⠀⠀⠀⠀⠀⢠⡖⠒⢦⡼⠀⠀⡼⢹⠀⠀⠀⣿⠀⠀⠀⠀⣼⠗⠛⠋⠁⠀⠀⠀⠀⠀⠈⠳
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢸⡋⠷⣄⣈⣗⣠⠴⠛⠛⠿⠿⠛⠋⠙⠶⠶⠶⠋⠹⣆⡀⠀⠀⠀⠀⠀⠉⠳⡄⠙⣆⠀⠁⢸⠀⠀⢠⡿⠀⠀⠀⣴⠿⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠱
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⣀⠘⣷⣄⠀⠀⠀⠀⠀⠀⠀⠀⠸⡀⠀⠀⠀⠀⠀⠀⠛⢾⣟⡛⠭⠭⠥⠖⠃⢀⡽⠀⢸⠀⣰⠏⠀⣠⣾⣁⢀⣠⠾⠋⡟⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⢠⢧⠀⠈⠛⠷⣦⣤⣤⣤⣤⣤⡴⢆⠙⠦⣤⣤⣠⣤⣤⡀⠀⠈⠙⠓⠶⠶⠶⠞⠋⠀⢀⡾⢸⣁⣤⠖⠋⠘⢿⣻⣧⣄⢸⣇⢠⠀⠀⠀⠀⠀⠀⠀⠀⠀⢰
-⠀⠀⠀⠀⠀⠀⠀⠀⠈⣿⡶⠦⠶⠞⠀⠀⠀⠀⠀⠀⠹⡆⠈⠉⠉⠉⠀⣠⠾⠛⠓⠦⣄⣀⡀⠀⠀⠀⣀⡠⠞⠀⢘⣇⠀⠀⠀⠀⠈⢳⡈⢻⣏⣿⣞⣆⠀⠀⢦⠀⠀⢀⣀⡠⠋
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⢈⣩⠽⠚⠃⠀⠀⠀⠀⠀⠀⢠⠙⢦⣄⣠⡤⠞⠁⠀⠀⠀⠀⠀⠉⠉⠉⠉⠉⠉⠀⠀⠀⠈⣿⡆⣠⡴⠂⠀⠀⣷⠀⣿⠈⠛⠻⠷⢦⣬⣿⠟⠋⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⣠⠴⠊⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⣀⠈⢷⣄⡀⠀⣀⣠⠶⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢿⣿⢿⡀⠀⠀⠶⢋⣼⠏⠀⠀⠀⠀⠈⢻⣆⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⣴⠟⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠘⠉⠉⠉⠉⠉⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢸⣿⠈⠛⠲⠴⠖⠋⠀⠀⠀⠀⠀⠀⠀⠀⢻⡆⠀⠀⠀⠀
-⠀⠀⢠⣾⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣾⡟⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢸⢲⢸⣿⠀⠀⠀⠀
-⠀⢠⣿⠃⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣴⡿⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⢠⡀⠀⢸⢸⠀⣿⠀⠀⠀⠀
-⠀⣿⡇⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣤⣾⠟⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢸⠇⠀⣸⣼⠀⡿⠀⠀⠀⠀
-⢰⣿⡇⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣠⡶⠟⠉⠁⠀⠀⠀⠀⠀⠀⠀⠀⢀⠀⠀⢀⡞⠀⣰⣿⠃⠀⠀⠀⠀⠀⠀
-⢸⣿⡇⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣴⠟⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⡞⠀⣰⠏⣠⡾⠋⠀⠀⠀⠀⠀⠀⠀⠀
-⠘⣿⣧⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⡼⠁⠀⣀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⡤⣎⣴⢾⣥⠞⠉⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⢿⣿⣆⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢠⠃⠀⠀⠀⠀⠀⠀⠀⢸⡇⠀⠀⠈⡇⠀⠀⠀⢀⣀⣠⣤⠴⠿⠛⠉⠀⠈⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⢻⣿⣆⠀⠀⠀⠀⠀⡄⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢰⠆⣾⠀⠀⠀⠀⠀⠀⠀⠀⠈⢷⣤⣤⠾⠛⠒⠚⠛⠛⠛⠉⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠻⣿⣷⣄⠀⠀⠀⢷⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢸⠀⠸⡄⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢠⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠈⠛⢿⣧⣄⡀⠘⣆⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠘⣧⠀⢻⣦⡀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⠏⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠈⠙⠻⠶⠿⣶⣄⣀⠀⠀⠀⠀⠀⣀⣤⠞⠙⢷⣄⡙⠻⣶⣤⣀⡀⠀⠀⢀⣠⡴⠋⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
-⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣙⡻⠿⣿⠿⠿⠿⠛⠁⠀⠀⠀⠈⣙⠛⠒⢛⠛⠛⠛⠛⠉⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀"#;
@@ -1,18 +1,40 @@
-use crate::input::Input;
+use crate::context::Context;
+use crate::actions::Action;
 use crate::screen::Screen;
-use crate::state_machine::State;
+use crate::state_machine::{State, StateChange};
+use crate::states::{DayInfo, DayKind};
+use crate::save::Outcome;
 use crate::states::main_state::MainState;
+use crate::states::pause_state::PauseState;
 use crate::states::transition_state::TransitionState;
+use crate::transition::Wipe;
 use crossterm::style;
 use rand::Rng;
-use std::time::{Duration, Instant};
+
+const TICK: f64 = 0.1;
+/// How fast the basket moves while a key is held, in cells per second.
+const BASKET_SPEED: f64 = 30.0;
+/// How long the final score is shown before going back to the calendar, in
+/// seconds.
+const SCORE_DURATION: f64 = 5.0;
+
+pub const DAY: DayInfo = DayInfo {
+    door: 4,
+    title: "day4.title",
+    author: "Faggruppe Rust",
+    kind: DayKind::Game,
+    unlocks_on: 4,
+    create: |ctx| Box::new(Day4State::new(ctx.screen.width())),
+};
 
 pub struct Day4State {
     snowflakes: Vec<Snowflake>,
-    basket_x: u16,
+    basket_x: f64,
     score: u32,
     time_left: f64,
-    last_update: Instant,
+    tick_timer: f64,
+    /// How long the final score has been shown, once the time is up.
+    score_shown: Option<f64>,
 }
 
 struct Snowflake {
@@ -24,28 +46,34 @@ impl Day4State {
     pub fn new(screen_width: u16) -> Self {
         Day4State {
             snowflakes: Vec::new(),
-            basket_x: screen_width / 2,
+            basket_x: (screen_width / 2) as f64,
             score: 0,
             time_left: 30.0,
-            last_update: Instant::now(),
+            tick_timer: 0.0,
+            score_shown: None,
         }
     }
 
+    fn move_basket(&mut self, screen_width: u16, distance: f64) {
+        self.basket_x = (self.basket_x + distance).clamp(1.0, (screen_width - 2) as f64);
+    }
+
     fn draw_basket(&mut self, screen: &mut Screen) {
+        let basket_x = self.basket_x as u16;
         screen.draw_text(
-            self.basket_x,
+            basket_x,
             screen.height() - 1,
             &"=",
             style::Color::Green,
         );
         screen.draw_text(
-            self.basket_x - 1,
+            basket_x - 1,
             screen.height() - 1,
             "[",
             style::Color::Green,
         );
         screen.draw_text(
-            self.basket_x + 1,
+            basket_x + 1,
             screen.height() - 1,
             "]",
             style::Color::Green,
@@ -54,49 +82,112 @@ impl Day4State {
 }
 
 impl State for Day4State {
-    fn enter(&mut self, screen: &mut Screen, _input: &mut Input) {
+    fn enter(&mut self, ctx: &mut Context) {
+        let Context { screen, .. } = ctx;
+
         screen.clear();
-        self.last_update = Instant::now();
+        self.tick_timer = 0.0;
     }
 
-    fn update(
-        &mut self,
-        screen: &mut Screen,
-        input: &mut Input,
-        dt: f64,
-    ) -> Option<Box<dyn State>> {
-        self.time_left -= dt;
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, progress, locale, .. } = ctx;
+
         if self.time_left <= 0.0 {
+            let shown = match self.score_shown {
+                Some(shown) => shown + dt,
+                None => {
+                    progress.complete(DAY.door, Outcome::Score(self.score));
+                    0.0
+                }
+            };
+            self.score_shown = Some(shown);
+
             screen.clear();
 
             screen.draw_text(
-                screen.height() / 2 - 10,
-                screen.width() / 2,
-                &format!("Time's up! Your final score: {}", self.score),
+                screen.width() / 2 - 10,
+                screen.height() / 2,
+                &locale.format("day4.time_up", &[("score", &self.score)]),
                 style::Color::White,
             );
-            std::thread::sleep(Duration::from_secs(5));
-            return Some(Box::new(TransitionState::new(
-                Box::new(MainState::new()),
-                None,
-            )));
-        }
 
-        if input.is_key_down('a') {
-            if self.basket_x > 1 {
-                self.basket_x -= 1;
+            if shown >= SCORE_DURATION {
+                return Some(StateChange::Replace(Box::new(
+                    TransitionState::new(Box::new(MainState::new()), None).effect(Wipe::horizontal()),
+                )));
             }
-        } else if input.is_key_down('d') {
-            if self.basket_x < screen.width() - 2 {
-                self.basket_x += 1;
+            return None;
+        }
+
+        // A tap always moves the basket one cell, holding the key moves it
+        // smoothly in `fixed_update`.
+        if input.is_action_pressed(Action::MoveLeft) {
+            self.move_basket(screen.width(), -1.0);
+        } else if input.is_action_pressed(Action::MoveRight) {
+            self.move_basket(screen.width(), 1.0);
+        }
+
+        screen.clear();
+
+        for snowflake in &self.snowflakes {
+            screen.set_cell(snowflake.x, snowflake.y, '*', style::Color::White);
+        }
+
+        self.draw_basket(screen);
+
+        screen.draw_text(
+            screen.width() / 2 - 10,
+            10,
+            &locale.format("day4.status", &[("score", &self.score), ("time", &format!("{:.1}", self.time_left))]),
+            style::Color::White,
+        );
+
+        screen.draw_text(
+            screen.width() / 2 - 10,
+            12,
+            &locale.format("day4.controls", &[
+                ("left", &input.actions().describe(Action::MoveLeft)),
+                ("right", &input.actions().describe(Action::MoveRight)),
+            ]),
+            style::Color::White,
+        );
+
+        // Pausing after drawing, so the game stays visible under the pause.
+        if input.is_action_pressed(Action::Pause) {
+            return Some(StateChange::Push(Box::new(PauseState::new())));
+        }
+
+        None
+    }
+
+    fn fixed_update(&mut self, ctx: &mut Context, dt: f64) {
+        let Context { screen, input, rng, .. } = ctx;
+
+        if self.time_left <= 0.0 {
+            return;
+        }
+
+        let left = input.is_action_held(Action::MoveLeft);
+        let right = input.is_action_held(Action::MoveRight);
+        let pressed = input.is_action_pressed(Action::MoveLeft)
+            || input.is_action_pressed(Action::MoveRight);
+
+        if !pressed {
+            if left && !right {
+                self.move_basket(screen.width(), -BASKET_SPEED * dt);
+            } else if right && !left {
+                self.move_basket(screen.width(), BASKET_SPEED * dt);
             }
         }
 
-        if self.last_update.elapsed() >= Duration::from_millis(100) {
-            self.last_update = Instant::now();
+        self.time_left -= dt;
+        self.tick_timer += dt;
 
-            if rand::thread_rng().gen_bool(0.3) {
-                let x = rand::thread_rng().gen_range(0..screen.width());
+        while self.tick_timer >= TICK {
+            self.tick_timer -= TICK;
+
+            if rng.random_bool(0.3) {
+                let x = rng.random_range(0..screen.width());
                 self.snowflakes.push(Snowflake { x, y: 0 });
             }
 
@@ -104,13 +195,14 @@ impl State for Day4State {
                 snowflake.y += 1;
             }
 
+            let basket_x = self.basket_x as u16;
             self.snowflakes.retain(|snowflake| {
                 if snowflake.y >= screen.height() {
                     false
                 } else if snowflake.y == screen.height() - 1
-                    && (snowflake.x == self.basket_x
-                        || snowflake.x == self.basket_x + 1
-                        || snowflake.x == self.basket_x - 1)
+                    && (snowflake.x == basket_x
+                        || snowflake.x == basket_x + 1
+                        || snowflake.x == basket_x - 1)
                 {
                     self.score += 1;
                     false
@@ -119,31 +211,45 @@ impl State for Day4State {
                 }
             });
         }
+    }
 
-        screen.clear();
+    fn exit(&mut self, _ctx: &mut Context) {}
 
-        for snowflake in &self.snowflakes {
-            screen.set_cell(snowflake.x, snowflake.y, '*', style::Color::White);
+    fn resize(&mut self, _ctx: &mut Context, width: u16, _height: u16) {
+        self.move_basket(width, 0.0);
+    }
+
+    fn focus_lost(&mut self, _ctx: &mut Context) -> Option<StateChange> {
+        if self.time_left > 0.0 {
+            return Some(StateChange::Push(Box::new(PauseState::new())));
         }
+        None
+    }
+}
 
-        self.draw_basket(screen);
+#[cfg(test)]
+mod tests {
+    use crate::context::testing::Harness;
+    use super::*;
 
-        screen.draw_text(
-            screen.width() / 2 - 10,
-            10,
-            &format!("Score: {}  Time Left: {:.1}s", self.score, self.time_left),
-            style::Color::White,
-        );
+    #[test]
+    fn score_is_shown_for_a_while_when_time_is_up() {
+        let mut harness = Harness::new((81, 40));
+        let mut state = Day4State::new(harness.ctx.screen.width());
+        state.enter(&mut harness.ctx);
+        state.score = 7;
+        state.time_left = 0.0;
 
-        screen.draw_text(
-            screen.width() / 2 - 10,
-            12,
-            &"Move with 'A' and 'D'",
-            style::Color::White,
-        );
+        assert!(state.update(&mut harness.ctx, 0.5).is_none());
+        harness.ctx.screen.render();
+        assert!(harness.ctx.progress.day(DAY.door).is_some_and(|day| day.is_completed()));
 
-        None
-    }
+        let text = harness.ctx.locale.format("day4.time_up", &[("score", &7)]);
+        assert_eq!(harness.terminal.borrow().line(20).trim(), text);
 
-    fn exit(&mut self, _screen: &mut Screen, _input: &mut Input) {}
+        for _ in 0..9 {
+            assert!(state.update(&mut harness.ctx, 0.5).is_none());
+        }
+        assert!(matches!(state.update(&mut harness.ctx, 0.5), Some(StateChange::Replace(_))));
+    }
 }
@@ -1,11 +1,27 @@
-use crate::drawing::{draw_ascii_safe_c, draw_text_box};
-use crate::input::{Input, MouseButton};
+use crate::context::Context;
+use crate::drawing::draw_text_box;
+use crate::actions::Action;
+use crate::art::Art;
+use crate::input::MouseButton;
 use crate::screen::Screen;
-use crate::state_machine::State;
+use crate::state_machine::{State, StateChange};
+use crate::states::{DayInfo, DayKind};
+use crate::save::Outcome;
 use crate::states::main_state::MainState;
+use crate::states::pause_state::PauseState;
 use crate::states::transition_state::TransitionState;
+use crate::transition::{Easing, Wipe};
 use crossterm::style;
-use rand::{thread_rng, Rng};
+use rand::Rng;
+
+pub const DAY: DayInfo = DayInfo {
+    door: 6,
+    title: "day6.title",
+    author: "Faggruppe Rust",
+    kind: DayKind::Game,
+    unlocks_on: 6,
+    create: |ctx| Box::new(Day6State::new(&mut ctx.rng, &ctx.screen, &ctx.art[CARD])),
+};
 
 #[derive(Clone)]
 struct Card {
@@ -15,18 +31,32 @@ struct Card {
     fraction: f64,
 }
 
+/// The card being dragged, and where on the card it was grabbed.
+struct HeldCard {
+    id: u8,
+    grab_x: f64,
+    grab_y: f64,
+}
+
 pub struct Day6State {
     cards: Vec<Card>,
     picked_cards: Vec<Card>,
+    held_card: Option<HeldCard>,
     timer: f64,
+    /// The size of the screen the cards are spread out on.
+    width: u16,
+    height: u16,
 }
 
 impl Day6State {
-    pub fn new(screen: &mut Screen) -> Self {
+    pub fn new(rng: &mut impl Rng, screen: &Screen, card_art: &Art) -> Self {
         Day6State {
-            cards: create_cards(screen.width(), screen.height()),
+            cards: create_cards(rng, card_art, screen.width(), screen.height()),
             picked_cards: vec![],
+            held_card: None,
             timer: 0.0,
+            width: screen.width(),
+            height: screen.height(),
         }
     }
 }
@@ -43,88 +73,169 @@ fn update_cards(cards: &mut Vec<Card>, x: f64, y: f64, dt: f64) {
     }
 }
 
+fn card_size(card_art: &Art) -> (f64, f64) {
+    (card_art.width() as f64, card_art.height() as f64)
+}
+
+fn is_over_card(card: &Card, card_art: &Art, x: f64, y: f64) -> bool {
+    let (width, height) = card_size(card_art);
+    x >= card.x.floor() && x < card.x.floor() + width && y >= card.y.floor() && y < card.y.floor() + height
+}
+
+fn pile_position(screen: &Screen, card_art: &Art) -> (f64, f64) {
+    (screen.width() as f64 / 2.0 - card_size(card_art).0 / 2.0, screen.height() as f64 / 2.0)
+}
+
+fn is_on_pile(card: &Card, card_art: &Art, screen: &Screen) -> bool {
+    let (pile_x, pile_y) = pile_position(screen, card_art);
+    let (width, height) = card_size(card_art);
+    (card.x - pile_x).abs() < width && (card.y - pile_y).abs() < height
+}
+
 fn lerp(a: f64, b: f64, t: f64) -> f64 {
     a + (b - a) * t
 }
 
 impl State for Day6State {
-    fn enter(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn enter(&mut self, _ctx: &mut Context) {
         self.timer = 0.0;
     }
 
-    fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) -> Option<Box<dyn State>> {
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, progress, locale, art, .. } = ctx;
+        let card_art = &art[CARD];
 
         let mouse_pos = input.mouse_position();
-        let mx = mouse_pos.0 as i16;
-        let my = mouse_pos.1 as i16;
-
-        update_cards(&mut self.picked_cards, screen.width() as f64 / 2.0 - CARD_WIDTH / 2.0, screen.height() as f64 / 2.0, dt);
-
-        for card in &self.picked_cards {
-            draw_ascii_safe_c(screen, CARD_ASCII, card.x as i16, card.y as i16, style::Color::Green);
+        let mx = mouse_pos.0 as f64;
+        let my = mouse_pos.1 as f64;
+
+        // Grab the topmost card under the mouse, and move it to the top.
+        if input.is_mouse_pressed(MouseButton::Left) {
+            if let Some(index) = self.cards.iter().rposition(|card| is_over_card(card, card_art, mx, my)) {
+                let card = self.cards.remove(index);
+                self.held_card = Some(HeldCard { id: card.id, grab_x: mx - card.x, grab_y: my - card.y });
+                self.cards.push(card);
+            }
         }
 
-        let mut to_remove: Vec<u8> = vec![];
-
-        for card in &self.cards {
-            if mx >= card.x as i16 && mx < card.x as i16 + CARD_WIDTH as i16 && my >= card.y as i16 && my < card.y as i16 + CARD_HEIGHT as i16 {
-                draw_ascii_safe_c(screen, CARD_ASCII, card.x as i16, card.y as i16, style::Color::Yellow);
+        if let (Some(held), Some(drag)) = (&self.held_card, input.drag()) {
+            if let Some(card) = self.cards.iter_mut().find(|card| card.id == held.id) {
+                card.x = drag.current.0 as f64 - held.grab_x;
+                card.y = drag.current.1 as f64 - held.grab_y;
+            }
+        }
 
-                if input.is_mouse_down(MouseButton::Left) {
-                    self.picked_cards.push(card.clone());
-                    to_remove.push(card.id);
+        // A card is tidied away when clicked, or when dropped on the pile.
+        if input.is_mouse_up(MouseButton::Left) {
+            if let Some(held) = self.held_card.take() {
+                let clicked = input.drag().is_none();
+                if let Some(index) = self.cards.iter().position(|card| card.id == held.id) {
+                    if clicked || is_on_pile(&self.cards[index], card_art, screen) {
+                        self.picked_cards.push(self.cards.remove(index));
+                        if self.cards.is_empty() {
+                            progress.complete(DAY.door, Outcome::Time(self.timer));
+                        }
+                    }
                 }
             }
-            else {
-                draw_ascii_safe_c(screen, CARD_ASCII, card.x as i16, card.y as i16, style::Color::White);
-            }
         }
 
-        for id in to_remove {
-            self.cards.retain(|card| card.id != id);
+        let (pile_x, pile_y) = pile_position(screen, card_art);
+        card_art.draw_colored(screen, "top_left", pile_x as i16, pile_y as i16, style::Color::DarkGrey);
+
+        for card in &self.picked_cards {
+            card_art.draw_colored(screen, "top_left", card.x as i16, card.y as i16, style::Color::Green);
+        }
+
+        let hovered = self.cards.iter().rposition(|card| is_over_card(card, card_art, mx, my));
+        for (i, card) in self.cards.iter().enumerate() {
+            let held = self.held_card.as_ref().is_some_and(|held| held.id == card.id);
+            let color = if held || (self.held_card.is_none() && hovered == Some(i)) {
+                style::Color::Yellow
+            } else {
+                style::Color::White
+            };
+            card_art.draw_colored(screen, "top_left", card.x as i16, card.y as i16, color);
         }
 
         if self.cards.is_empty() {
-            let string = format!("  Bra jobba! Du klarte det på {:.2} sekunder.", self.timer);
+            let string = format!("  {}", locale.format("day6.done", &[("time", &format!("{:.2}", self.timer))]));
             draw_text_box(screen, screen.width(), screen.height(), &string, 0, -8, (0, 0), false);
 
-            let hovered = draw_text_box(screen, screen.width(), screen.height(), "Tilbake", 0, -4, input.mouse_position(), input.is_mouse_down(MouseButton::Left));
-            if hovered && input.is_mouse_up(MouseButton::Left) {
-                return Some(Box::new(TransitionState::new(Box::new(MainState::new()), None)));
+            let hovered = draw_text_box(screen, screen.width(), screen.height(), &locale.text("common.back"), 0, -4, input.mouse_position(), input.is_mouse_down(MouseButton::Left));
+            if (hovered && input.is_mouse_up(MouseButton::Left)) || input.is_action_pressed(Action::Back) {
+                let transition = TransitionState::new(Box::new(MainState::new()), None)
+                    .effect(Wipe::vertical())
+                    .easing(Easing::EaseOut);
+                return Some(StateChange::Replace(Box::new(transition)));
             }
         }
         else {
             self.timer += dt;
 
-            draw_text_box(screen, screen.width(), screen.height(),"    Din nevø på 7 har vært på besøk.", 0, -8, (0, 0), false);
-            draw_text_box(screen, screen.width(), screen.height()," Rydd opp alle kortene han kastet ut på gulvet.", 0, -4, (0, 0), false);
+            draw_text_box(screen, screen.width(), screen.height(), &format!("    {}", locale.text("day6.visit")), 0, -8, (0, 0), false);
+            draw_text_box(screen, screen.width(), screen.height(), &format!(" {}", locale.text("day6.task")), 0, -4, (0, 0), false);
+
+            if input.is_action_pressed(Action::Pause) {
+                return Some(StateChange::Push(Box::new(PauseState::new())));
+            }
+        }
+
+        None
+    }
+
+    fn fixed_update(&mut self, ctx: &mut Context, dt: f64) {
+        let Context { screen, art, .. } = ctx;
+
+        let (pile_x, pile_y) = pile_position(screen, &art[CARD]);
+        update_cards(&mut self.picked_cards, pile_x, pile_y, dt);
+    }
+
+    fn exit(&mut self, _ctx: &mut Context) {
+    }
+
+    /// Moves the cards on the floor along with the edges of the screen, so
+    /// they are spread out the same way and none end up outside it.
+    fn resize(&mut self, ctx: &mut Context, width: u16, height: u16) {
+        let (card_width, card_height) = card_size(&ctx.art[CARD]);
+        let scale_x = width as f64 / self.width.max(1) as f64;
+        let scale_y = height as f64 / self.height.max(1) as f64;
+        let max_x = (width as f64 - card_width).max(0.0);
+        let max_y = (height as f64 - card_height).max(0.0);
+
+        for card in &mut self.cards {
+            card.x = (card.x * scale_x).clamp(0.0, max_x);
+            card.y = (card.y * scale_y).clamp(0.0, max_y);
         }
 
+        self.width = width;
+        self.height = height;
+    }
+
+    fn focus_lost(&mut self, _ctx: &mut Context) -> Option<StateChange> {
+        if !self.cards.is_empty() {
+            return Some(StateChange::Push(Box::new(PauseState::new())));
+        }
         None
     }
 
-    fn exit(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn pause(&mut self, _ctx: &mut Context) {
+        // The mouse button is let go of somewhere in the pause menu.
+        self.held_card = None;
     }
 }
 
-fn create_cards(width: u16, height: u16) -> Vec<Card> {
-    let mut rng = thread_rng();
+fn create_cards(rng: &mut impl Rng, card_art: &Art, width: u16, height: u16) -> Vec<Card> {
+    let (card_width, card_height) = card_size(card_art);
 
     let mut cards = vec![];
     for i in 0..52 {
-        let x = rng.gen_range(CARD_WIDTH..width as f64 - CARD_WIDTH);
-        let y = rng.gen_range(CARD_HEIGHT..height as f64 - CARD_HEIGHT);
+        let x = rng.random_range(card_width..width as f64 - card_width);
+        let y = rng.random_range(card_height..height as f64 - card_height);
         cards.push(Card { id: i, x, y, fraction: 0.0 });
     }
     cards
 }
 
-
-const CARD_WIDTH: f64 = 7.0;
-const CARD_HEIGHT: f64 = 4.0;
-
-const CARD_ASCII: &str =
-"┌─────┐
-│⠀⠀⠀⠀⠀│
-│⠀⠀⠀⠀⠀│
-└─────┘";
\ No newline at end of file
+/// The art every card is drawn with.
+const CARD: &str = "card";
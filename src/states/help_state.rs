@@ -0,0 +1,107 @@
+use crossterm::style::Color;
+use crate::actions::Action;
+use crate::context::Context;
+use crate::drawing::draw_text_box;
+use crate::input::MouseButton;
+use crate::screen::Style;
+use crate::state_machine::{State, StateChange, Underlay};
+
+/// Lists every action and the keys bound to it. The state underneath keeps
+/// running, so the snow in the calendar keeps falling behind the list.
+pub struct HelpState;
+
+impl HelpState {
+    pub fn new() -> Self {
+        HelpState
+    }
+}
+
+impl State for HelpState {
+    fn enter(&mut self, _ctx: &mut Context) {}
+
+    fn update(&mut self, ctx: &mut Context, _dt: f64) -> Option<StateChange> {
+        let Context { screen, input, locale, .. } = ctx;
+        let (width, height) = (screen.width(), screen.height());
+
+        let lines: Vec<String> = Action::ALL
+            .iter()
+            .map(|action| locale.format("help.binding", &[
+                ("action", &locale.text(&format!("action.{}", action.name()))),
+                ("keys", &input.actions().describe(*action)),
+            ]))
+            .collect();
+
+        // The list is drawn on a blank panel, so what is running underneath
+        // does not get mixed into the text.
+        let columns = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as u16 + 4;
+        let rows = lines.len() as u16 + 2;
+        let x = width.saturating_sub(columns) / 2;
+        let y = height.saturating_sub(rows) / 2;
+        for row in y..y + rows {
+            for column in x..x + columns {
+                screen.set_cell(column, row, ' ', Style::default());
+            }
+        }
+        for (i, line) in lines.iter().enumerate() {
+            screen.draw_text(x + 2, y + 1 + i as u16, line, Color::White);
+        }
+
+        let clicked = input.is_mouse_up(MouseButton::Left);
+        let offset = rows as i16 / 2 + 3;
+        draw_text_box(screen, width, height, &format!("  {}  ", locale.text("help.title")), 0, -offset, (0, 0), false);
+        let close = draw_text_box(screen, width, height, &locale.text("help.close"), 0, offset, input.mouse_position(), clicked);
+
+        if (close && clicked) || input.is_action_pressed(Action::Help) || input.is_action_pressed(Action::Back) {
+            return Some(StateChange::Pop);
+        }
+
+        None
+    }
+
+    fn exit(&mut self, _ctx: &mut Context) {}
+
+    fn underlay(&self) -> Underlay {
+        Underlay::Running
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use crate::context::testing::Harness;
+    use crate::state_machine::StateMachine;
+    use crate::states::main_state::MainState;
+    use super::*;
+
+    fn frame(harness: &mut Harness, state_machine: &mut StateMachine) {
+        harness.ctx.input.update(1.0 / 60.0).unwrap();
+        harness.ctx.screen.clear();
+        state_machine.update(&mut harness.ctx, 1.0 / 60.0);
+        harness.ctx.screen.render();
+    }
+
+    #[test]
+    fn help_opens_over_the_running_calendar() {
+        let mut harness = Harness::new((121, 40));
+        let mut state_machine = StateMachine::new();
+        state_machine.push(&mut harness.ctx, Box::new(MainState::new()));
+
+        harness.send(Event::Key(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE)));
+        frame(&mut harness, &mut state_machine);
+        frame(&mut harness, &mut state_machine);
+
+        let quit = harness.ctx.locale.format("help.binding", &[
+            ("action", &harness.ctx.locale.text("action.quit")),
+            ("keys", &harness.ctx.input.actions().describe(Action::Quit)),
+        ]);
+        let contents = harness.terminal.borrow().contents();
+        assert!(contents.contains(&quit));
+        // The calendar is still drawn around the list.
+        assert!(contents.contains("│  01  │"));
+
+        harness.send(Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        frame(&mut harness, &mut state_machine);
+        frame(&mut harness, &mut state_machine);
+        assert!(!harness.terminal.borrow().contents().contains(&quit));
+    }
+}
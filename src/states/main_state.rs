@@ -1,15 +1,43 @@
-use crate::drawing::{draw_ascii, draw_calendar, draw_ground};
-use crate::state_machine::State;
-use crate::screen::Screen;
-use crate::{ascii, snowflakes, states};
-use crate::input::{Input, MouseButton};
+use crate::context::Context;
+use crate::actions::Action;
+use crate::calendar::Calendar;
+use crate::drawing::{draw_calendar, draw_door_opening, draw_ground, draw_text_box, Door};
+use crate::state_machine::{State, StateChange};
+use crate::snowflakes;
+use crate::input::MouseButton;
+use crate::locale::Locale;
+use crate::save::{DayProgress, Progress};
 use crate::snowflakes::Snowflake;
+use crate::states::help_state::HelpState;
+use crate::states::transition_state::TransitionState;
+use crate::states::{Day, Days};
+use crate::transition::Iris;
+use std::time::Duration;
+
+/// How long the message about a locked door stays up, in seconds.
+const LOCKED_MESSAGE_TIME: f64 = 3.0;
+
+/// How long a door takes to swing open before the day starts, in seconds.
+const DOOR_OPENING_TIME: f64 = 0.5;
+
+/// A door that is swinging open.
+struct OpeningDoor {
+    day: Day,
+    time: f64,
+}
+
+/// A locked door that was clicked, and for how much longer to say so.
+struct LockedDoor {
+    days_left: i64,
+    door: usize,
+    time_left: f64,
+}
 
 pub struct MainState {
     snowflakes: Vec<Snowflake>,
     phase: f64,
-    prev_width: u16,
-    prev_height: u16,
+    locked_door: Option<LockedDoor>,
+    opening_door: Option<OpeningDoor>,
 }
 
 impl MainState {
@@ -17,78 +45,151 @@ impl MainState {
         MainState {
             snowflakes: Vec::new(),
             phase: 0.0,
-            prev_width: 0,
-            prev_height: 0,
+            locked_door: None,
+            opening_door: None,
         }
     }
 }
 
 impl State for MainState {
-    fn enter(&mut self, screen: &mut Screen, input: &mut Input) {
-        self.prev_width = screen.width();
-        self.prev_height = screen.height();
-        self.snowflakes = snowflakes::create(screen.width(), screen.height());
+    fn enter(&mut self, ctx: &mut Context) {
+        let Context { screen, rng, .. } = ctx;
+
+        self.snowflakes = snowflakes::create(rng, screen.width(), screen.height());
     }
 
-    fn update(&mut self, screen: &mut Screen, input: &mut Input, dt: f64) -> Option<Box<dyn State>> {
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, calendar, progress, days, locale, art, .. } = ctx;
+
         let screen_height = screen.height();
         let screen_width = screen.width();
 
-        if self.prev_width != screen_width || self.prev_height != screen_height {
-            self.prev_width = screen_width;
-            self.prev_height = screen_height;
-            self.snowflakes = snowflakes::create(screen_width, screen_height);
-        }
+        art["santa"].draw(screen, "bottom_left", 2, screen_height as i16 - 2);
+        snowflakes::draw(screen, &self.snowflakes);
+        art["systek"].draw(screen, "top", screen_width as i16 / 2, 2);
+        draw_ground(screen);
 
-        self.phase += dt;
+        let hovered = draw_calendar(screen, input.mouse_position(), input.is_mouse_up(MouseButton::Left), |door| {
+            door_state(calendar, progress, days, door)
+        });
+        let hovered_day = hovered.and_then(|door| days.get(door));
 
-        snowflakes::update(&mut self.snowflakes, screen_width, screen_height, self.phase, dt);
-        if input.is_mouse_down(MouseButton::Left) {
-            snowflakes::spawn_mouse_snow_flakes(&mut self.snowflakes, input.mouse_position())
+        // Switches to the next language, named in that language.
+        let next_language = locale.next_language().to_string();
+        let language_name = locale.language_name(&next_language);
+        let len = language_name.len() as i16;
+        let x_offset = screen_width as i16 - len - 4 - (screen_width as i16 - len) / 2;
+        let y_offset = 1 - screen_height as i16 / 2;
+        let language_hovered = draw_text_box(screen, screen_width, screen_height, &language_name, x_offset, y_offset, input.mouse_position(), input.is_mouse_down(MouseButton::Left));
+        if language_hovered && input.is_mouse_up(MouseButton::Left) {
+            let _ = locale.set_language(&next_language);
         }
 
-        draw_ascii(screen, ascii::SANTA, 2, screen_height - 20);
-        snowflakes::draw(screen, &self.snowflakes);
-        draw_ascii(screen, ascii::SYSTEK, screen_width / 2 - 32, 1);
-        draw_ground(screen);
+        if let Some(locked) = &mut self.locked_door {
+            let message = locale.plural("calendar.locked", locked.days_left, &[("door", &locked.door)]);
+            draw_text_box(screen, screen_width, screen_height, &message, 0, 10, (0, 0), false);
+
+            locked.time_left -= dt;
+            if locked.time_left <= 0.0 {
+                self.locked_door = None;
+            }
+        } else if let Some(day) = hovered_day.filter(|day| calendar.is_unlocked(day.unlocks_on())) {
+            let description = locale.format("calendar.door", &[
+                ("door", &day.door()),
+                ("title", &day.title(locale)),
+                ("kind", &day.kind().name(locale)),
+                ("author", &day.author()),
+            ]);
+            draw_text_box(screen, screen_width, screen_height, &description, 0, 10, (0, 0), false);
+
+            if let Some(summary) = progress.day(day.door()).and_then(|progress| describe_progress(locale, progress)) {
+                draw_text_box(screen, screen_width, screen_height, &summary, 0, 13, (0, 0), false);
+            }
+        }
 
-        if let Some(ref day) = draw_calendar(screen, input.mouse_position(), input.is_mouse_up(MouseButton::Left)) {
-            let next: Option<Box<dyn State>> =  match day {
-                1 => Some(Box::new(states::day1_state::Day1State::new())),
-                2 => Some(Box::new(states::day2_state::Day2State::new())),
-                3 => Some(Box::new(states::day3_state::Day3State::new())),
-                4 => Some(Box::new(states::day4_state::Day4State::new(screen_width))),
-                5 => Some(Box::new(states::day5_state::Day5State::new())),
-                6 => Some(Box::new(states::day6_state::Day6State::new(screen))),
-                // 7 => Some(Box::new(states::day7_state::Day7State::new())),
-                // 8 => Some(Box::new(states::day8_state::Day8State::new())),
-                // 9 => Some(Box::new(states::day9_state::Day9State::new())),
-                // 10 => Some(Box::new(states::day10_state::Day10State::new())),
-                // 11 => Some(Box::new(states::day11_state::Day11State::new())),
-                12 => Some(Box::new(states::day12_state::Day12State::new())),
-                // 13 => Some(Box::new(states::day13_state::Day13State::new())),
-                14 => Some(Box::new(states::day14_state::Day14State::new())),
-                // 15 => Some(Box::new(states::day15_state::Day15State::new())),
-                // 16 => Some(Box::new(states::day16_state::Day16State::new())),
-                // 17 => Some(Box::new(states::day17_state::Day17State::new())),
-                // 18 => Some(Box::new(states::day18_state::Day18State::new())),
-                // 19 => Some(Box::new(states::day19_state::Day19State::new())),
-                // 20 => Some(Box::new(states::day20_state::Day20State::new())),
-                // 21 => Some(Box::new(states::day21_state::Day21State::new())),
-                // 22 => Some(Box::new(states::day22_state::Day22State::new())),
-                // 23 => Some(Box::new(states::day23_state::Day23State::new())),
-                24 => Some(Box::new(states::day24_state::Day24State::new())),
-                _ => None,
-            };
-
-            if next.is_some() {
-                return Some(Box::new(states::transition_state::TransitionState::new(next.unwrap(), None)));
+        if let Some(opening) = &mut self.opening_door {
+            opening.time += dt;
+            draw_door_opening(screen, opening.day.door(), opening.time / DOOR_OPENING_TIME);
+            if opening.time < DOOR_OPENING_TIME {
+                return None;
             }
+
+            let day = self.opening_door.take()?.day;
+            ctx.progress.attempt(day.door());
+            let transition = TransitionState::new(day.create(ctx), None)
+                .effect(Iris)
+                .duration(Duration::from_millis(800));
+            return Some(StateChange::Replace(Box::new(transition)));
+        }
+
+        if input.is_action_pressed(Action::Help) {
+            return Some(StateChange::Push(Box::new(HelpState::new())));
+        }
+
+        let day = hovered_day.filter(|_| input.is_mouse_up(MouseButton::Left))?;
+
+        if !calendar.is_unlocked(day.unlocks_on()) {
+            self.locked_door = Some(LockedDoor {
+                days_left: calendar.days_until(day.unlocks_on()),
+                door: day.door(),
+                time_left: LOCKED_MESSAGE_TIME,
+            });
+            return None;
         }
 
+        self.opening_door = Some(OpeningDoor { day: day.clone(), time: 0.0 });
         None
     }
 
-    fn exit(&mut self, screen: &mut Screen, input: &mut Input) {
+    fn fixed_update(&mut self, ctx: &mut Context, dt: f64) {
+        let Context { screen, input, rng, .. } = ctx;
+
+        self.phase += dt;
+
+        snowflakes::update(rng, &mut self.snowflakes, screen.width(), screen.height(), self.phase, dt);
+        if input.is_mouse_down(MouseButton::Left) {
+            snowflakes::spawn_mouse_snow_flakes(rng, &mut self.snowflakes, input.mouse_position())
+        }
+    }
+
+    fn exit(&mut self, _ctx: &mut Context) {
+    }
+
+    fn resize(&mut self, ctx: &mut Context, width: u16, height: u16) {
+        self.snowflakes = snowflakes::create(&mut ctx.rng, width, height);
+    }
+}
+
+fn door_state(calendar: &Calendar, progress: &Progress, days: &Days, door: usize) -> Door {
+    let Some(day) = days.get(door) else {
+        return Door::Empty;
+    };
+    if !calendar.is_unlocked(day.unlocks_on()) {
+        return Door::Locked;
+    }
+
+    match progress.day(door) {
+        Some(progress) if progress.is_completed() => Door::Completed,
+        Some(progress) if progress.attempts > 0 => Door::Opened,
+        _ => Door::Available,
+    }
+}
+
+/// A line about how the player has done on a day, once it is completed.
+fn describe_progress(locale: &Locale, progress: &DayProgress) -> Option<String> {
+    if !progress.is_completed() {
+        return None;
+    }
+
+    let mut summary = locale.plural("calendar.completed", progress.attempts as i64, &[("completions", &progress.completions)]);
+    if let Some(time) = progress.best_time {
+        summary += &locale.format("calendar.best_time", &[("time", &format!("{:.2}", time))]);
     }
-}
\ No newline at end of file
+    if let Some(score) = progress.best_score {
+        summary += &locale.format("calendar.best_score", &[("score", &score)]);
+    }
+    if let Some(moves) = progress.fewest_moves {
+        summary += &locale.format("calendar.fewest_moves", &[("moves", &moves)]);
+    }
+    Some(summary)
+}
@@ -1,11 +1,142 @@
+use std::rc::Rc;
+use crate::context::Context;
+use crate::locale::Locale;
+use crate::quiz::Quiz;
+use crate::state_machine::State;
+use crate::states::quiz_state::QuizState;
+
 pub mod transition_state;
 pub mod main_state;
-pub mod day1_state;
-pub mod day2_state;
-pub mod day3_state;
-pub mod day4_state;
-pub mod day5_state;
-pub mod day6_state;
-pub mod day12_state;
-pub mod day14_state;
-pub mod day24_state;
+pub mod help_state;
+pub mod pause_state;
+pub mod quiz_state;
+
+/// What kind of challenge is behind a door.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DayKind {
+    Quiz,
+    Game,
+    Puzzle,
+    Picture,
+}
+
+impl DayKind {
+    pub fn name(self, locale: &Locale) -> String {
+        locale.text(match self {
+            DayKind::Quiz => "kind.quiz",
+            DayKind::Game => "kind.game",
+            DayKind::Puzzle => "kind.puzzle",
+            DayKind::Picture => "kind.picture",
+        })
+    }
+}
+
+/// Everything the calendar needs to know about a day written in Rust. Every
+/// day module exports one as `DAY`.
+pub struct DayInfo {
+    pub door: usize,
+    /// The catalog message with the title.
+    pub title: &'static str,
+    pub author: &'static str,
+    pub kind: DayKind,
+    /// The day in December the door opens.
+    pub unlocks_on: usize,
+    pub create: fn(&mut Context) -> Box<dyn State>,
+}
+
+/// Declares the day modules, and lists their `DAY` in `DAYS`. Adding a day
+/// is a matter of writing its module and naming it here.
+macro_rules! days {
+    ($($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub const DAYS: &[DayInfo] = &[$($module::DAY),*];
+    };
+}
+
+days![
+    day2_state,
+    day4_state,
+    day6_state,
+    day14_state,
+    day24_state,
+];
+
+/// What is behind a door: a day written in Rust, or a quiz from the content
+/// files.
+#[derive(Clone)]
+pub enum Day {
+    Code(&'static DayInfo),
+    Quiz(Rc<Quiz>),
+}
+
+impl Day {
+    pub fn door(&self) -> usize {
+        match self {
+            Day::Code(info) => info.door,
+            Day::Quiz(quiz) => quiz.door,
+        }
+    }
+
+    pub fn title(&self, locale: &Locale) -> String {
+        match self {
+            Day::Code(info) => locale.text(info.title),
+            Day::Quiz(quiz) => quiz.title.get(locale).to_string(),
+        }
+    }
+
+    pub fn author(&self) -> &str {
+        match self {
+            Day::Code(info) => info.author,
+            Day::Quiz(quiz) => &quiz.author,
+        }
+    }
+
+    pub fn kind(&self) -> DayKind {
+        match self {
+            Day::Code(info) => info.kind,
+            Day::Quiz(_) => DayKind::Quiz,
+        }
+    }
+
+    /// The day in December the door opens.
+    pub fn unlocks_on(&self) -> usize {
+        match self {
+            Day::Code(info) => info.unlocks_on,
+            Day::Quiz(quiz) => quiz.unlocks_on(),
+        }
+    }
+
+    pub fn create(&self, ctx: &mut Context) -> Box<dyn State> {
+        match self {
+            Day::Code(info) => (info.create)(ctx),
+            Day::Quiz(quiz) => Box::new(QuizState::new(quiz.clone(), &mut ctx.rng)),
+        }
+    }
+}
+
+/// Every day in the calendar.
+pub struct Days {
+    days: Vec<Day>,
+}
+
+impl Days {
+    /// The days in `DAYS`, and a day for each quiz.
+    pub fn new(quizzes: Vec<Quiz>) -> Result<Days, String> {
+        let mut days: Vec<Day> = DAYS.iter().map(Day::Code).collect();
+
+        for quiz in quizzes {
+            if days.iter().any(|day| day.door() == quiz.door) {
+                return Err(format!("door {} has more than one day behind it", quiz.door));
+            }
+            days.push(Day::Quiz(Rc::new(quiz)));
+        }
+
+        Ok(Days { days })
+    }
+
+    /// The day behind `door`, if there is one yet.
+    pub fn get(&self, door: usize) -> Option<&Day> {
+        self.days.iter().find(|day| day.door() == door)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::context::Context;
+use crate::actions::Action;
+use crate::drawing::draw_text_box;
+use crate::input::MouseButton;
+use crate::state_machine::{State, StateChange, Underlay};
+use crate::states::main_state::MainState;
+use crate::states::transition_state::TransitionState;
+use crate::transition::{Dissolve, Easing};
+
+/// Shown on top of a day when the game is paused. The day is frozen
+/// underneath, and continues where it left off when the pause is over.
+pub struct PauseState;
+
+impl PauseState {
+    pub fn new() -> Self {
+        PauseState
+    }
+}
+
+impl State for PauseState {
+    fn enter(&mut self, _ctx: &mut Context) {}
+
+    fn update(&mut self, ctx: &mut Context, _dt: f64) -> Option<StateChange> {
+        let Context { screen, input, locale, .. } = ctx;
+
+        let clicked = input.is_mouse_up(MouseButton::Left);
+
+        draw_text_box(screen, screen.width(), screen.height(), &format!("  {}  ", locale.text("pause.title")), 0, -4, (0, 0), false);
+
+        let resume = draw_text_box(screen, screen.width(), screen.height(), &locale.text("pause.resume"), 0, 0, input.mouse_position(), clicked);
+        let quit = draw_text_box(screen, screen.width(), screen.height(), &locale.text("common.back_to_calendar"), 0, 4, input.mouse_position(), clicked);
+
+        if (resume && clicked) || input.is_action_pressed(Action::Pause) || input.is_action_pressed(Action::Back) {
+            return Some(StateChange::Pop);
+        }
+
+        if quit && clicked {
+            return Some(StateChange::Reset(Box::new(TransitionState::new(Box::new(MainState::new()), None).effect(Dissolve::new()).easing(Easing::EaseIn))));
+        }
+
+        None
+    }
+
+    fn exit(&mut self, _ctx: &mut Context) {}
+
+    fn underlay(&self) -> Underlay {
+        Underlay::Frozen
+    }
+}
@@ -0,0 +1,407 @@
+use std::rc::Rc;
+use crossterm::style::Color;
+use crate::actions::Action;
+use crate::context::Context;
+use crate::input::MouseButton;
+use crate::locale::{Locale, Text};
+use crate::screen::Screen;
+use crate::state_machine::{State, StateChange};
+use crate::save::Outcome;
+use rand::Rng;
+use crate::drawing::{draw_question, draw_text_box};
+use crate::quiz::{Effect, Question, Quiz};
+use crate::snowflakes::{self, Snowflake};
+use crate::states::Day;
+use crate::states::main_state::MainState;
+use crate::states::pause_state::PauseState;
+use crate::states::transition_state::TransitionState;
+
+/// Points for a correct answer, before bonuses.
+const POINTS_PER_ANSWER: u32 = 100;
+/// Bonus points for every whole second left when answering correctly.
+const POINTS_PER_SECOND_LEFT: u32 = 5;
+/// Bonus points for every correct answer in a row before this one.
+const STREAK_BONUS: u32 = 50;
+
+/// How many columns the explanation of an answer is wrapped to.
+const EXPLANATION_WIDTH: usize = 60;
+/// How many columns the timer takes when full.
+const TIMER_WIDTH: u16 = 40;
+
+struct Particle {
+    x: f64,
+    y: f64,
+    sprite: char,
+}
+
+enum Phase {
+    /// Waiting for an answer to the current question.
+    Asking { time_left: f64 },
+    /// Showing whether the answer was right, and why. `answer` is `None`
+    /// when time ran out.
+    Revealing { answer: Option<usize>, points: u32 },
+    /// Every question is answered.
+    Finished,
+}
+
+/// A day that asks the questions in a quiz file, one after the other.
+pub struct QuizState {
+    quiz: Rc<Quiz>,
+    /// The current question.
+    question: usize,
+    /// The answers to the current question, in the order they are shown.
+    answers: Vec<Text>,
+    correct_answer_position: usize,
+    phase: Phase,
+
+    score: u32,
+    correct_answers: usize,
+    streak: u32,
+    longest_streak: u32,
+
+    time: f64,
+    /// The Z's of each piece of art. Empty unless the art is snoring.
+    particles: Vec<Vec<Particle>>,
+    snowflakes: Vec<Snowflake>,
+}
+
+impl QuizState {
+    pub fn new(quiz: Rc<Quiz>, rng: &mut impl Rng) -> Self {
+        let mut state = QuizState {
+            quiz,
+            question: 0,
+            answers: vec![],
+            correct_answer_position: 0,
+            phase: Phase::Finished,
+
+            score: 0,
+            correct_answers: 0,
+            streak: 0,
+            longest_streak: 0,
+
+            time: 0.0,
+            particles: vec![],
+            snowflakes: vec![],
+        };
+        state.ask(0, rng);
+        state
+    }
+
+    fn has_effect(&self, effect: Effect) -> bool {
+        self.quiz.effects.contains(&effect)
+    }
+
+    fn current_question(&self) -> &Question {
+        &self.quiz.questions[self.question]
+    }
+
+    /// Moves on to question number `question`, with the answers in a new
+    /// random order.
+    fn ask(&mut self, question: usize, rng: &mut impl Rng) {
+        self.question = question;
+
+        let question = self.current_question();
+        let mut answers = question.wrong_answers.clone();
+        let correct_answer_position = rng.random_range(0..answers.len() + 1);
+        answers.insert(correct_answer_position, question.correct_answer.clone());
+
+        self.answers = answers;
+        self.correct_answer_position = correct_answer_position;
+        self.phase = Phase::Asking { time_left: self.quiz.time_limit(self.current_question()) };
+    }
+
+    /// Scores `answer`, or running out of time if it is `None`.
+    fn answer(&mut self, answer: Option<usize>, time_left: f64) {
+        let mut points = 0;
+        if answer == Some(self.correct_answer_position) {
+            points = POINTS_PER_ANSWER + POINTS_PER_SECOND_LEFT * time_left as u32 + STREAK_BONUS * self.streak;
+            self.correct_answers += 1;
+            self.streak += 1;
+            self.longest_streak = self.longest_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+
+        self.score += points;
+        self.phase = Phase::Revealing { answer, points };
+    }
+
+    fn draw_status(&self, screen: &mut Screen, locale: &Locale) {
+        let status = locale.format("quiz.status", &[
+            ("question", &(self.question + 1)),
+            ("questions", &self.quiz.questions.len()),
+            ("score", &self.score),
+            ("streak", &self.streak),
+        ]);
+        draw_text_box(screen, screen.width(), screen.height(), &status, 0, -10, (0, 0), false);
+    }
+
+    fn draw_reveal(&self, screen: &mut Screen, locale: &Locale, answer: Option<usize>, points: u32) {
+        let (width, height) = (screen.width(), screen.height());
+
+        let verdict = match answer {
+            Some(answer) if answer == self.correct_answer_position => match self.streak {
+                0 | 1 => locale.format("quiz.correct", &[("points", &points)]),
+                streak => locale.format("quiz.correct_streak", &[("points", &points), ("streak", &streak)]),
+            },
+            Some(_) => locale.text("quiz.wrong"),
+            None => locale.text("quiz.time_up"),
+        };
+        draw_text_box(screen, width, height, &verdict, 0, -5, (0, 0), false);
+
+        let correct_answer = locale.format("quiz.answer", &[("answer", &self.current_question().correct_answer.get(locale))]);
+        draw_text_box(screen, width, height, &correct_answer, 0, -1, (0, 0), false);
+
+        if let Some(explanation) = &self.current_question().explanation {
+            for (i, line) in wrap(explanation.get(locale), EXPLANATION_WIDTH).iter().enumerate() {
+                let x = (width as i16 - line.chars().count() as i16).max(0) / 2;
+                let y = height as i16 / 2 + 2 + i as i16;
+                if y >= 0 {
+                    screen.draw_text(x as u16, y as u16, line, Color::White);
+                }
+            }
+        }
+    }
+
+    fn draw_summary(&self, screen: &mut Screen, locale: &Locale) {
+        let (width, height) = (screen.width(), screen.height());
+
+        draw_text_box(screen, width, height, &locale.text("quiz.finished"), 0, -5, (0, 0), false);
+        let summary = locale.format("quiz.summary", &[
+            ("correct", &self.correct_answers),
+            ("count", &self.quiz.questions.len()),
+            ("score", &self.score),
+            ("streak", &self.longest_streak),
+        ]);
+        draw_text_box(screen, width, height, &summary, 0, -1, (0, 0), false);
+    }
+}
+
+impl State for QuizState {
+    fn enter(&mut self, ctx: &mut Context) {
+        let Context { screen, rng, .. } = ctx;
+
+        self.particles = self.quiz.art.iter()
+            .map(|art| if art.snoring { create_particles() } else { vec![] })
+            .collect();
+        if self.has_effect(Effect::Snow) {
+            self.snowflakes = snowflakes::create(rng, screen.width(), screen.height());
+        }
+    }
+
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        let Context { screen, input, progress, rng, locale, art: library, .. } = ctx;
+
+        self.time += dt;
+
+        for (art, particles) in self.quiz.art.iter().zip(&mut self.particles) {
+            let (x, y) = art.position(screen.width(), screen.height());
+            let found = &library[art.name.as_str()];
+            found.draw(screen, &art.anchor, x, y);
+
+            let (origin_x, origin_y) = found.origin(&art.anchor, x, y);
+            let (snore_x, snore_y) = found.anchor("snore").unwrap_or((0, 0));
+            draw_particles(screen, particles, origin_x + snore_x, origin_y + snore_y, self.time, dt);
+        }
+        snowflakes::draw(screen, &self.snowflakes);
+
+        let mouse_position = input.mouse_position();
+        let clicked = input.is_mouse_up(MouseButton::Left);
+
+        match self.phase {
+            Phase::Asking { time_left } => {
+                self.draw_status(screen, locale);
+
+                let answers: Vec<&str> = self.answers.iter().map(|answer| answer.get(locale)).collect();
+                let answer = draw_question(screen, mouse_position, clicked, self.current_question().question.get(locale), &answers);
+                draw_timer(screen, time_left / self.quiz.time_limit(self.current_question()), time_left);
+
+                // Pausing after drawing, so the question stays visible under
+                // the pause, and before the timer runs.
+                if input.is_action_pressed(Action::Pause) {
+                    return Some(StateChange::Push(Box::new(PauseState::new())));
+                }
+
+                let time_left = time_left - dt;
+                if answer.is_some() || time_left <= 0.0 {
+                    self.answer(answer, time_left.max(0.0));
+                } else {
+                    self.phase = Phase::Asking { time_left };
+                }
+            }
+            Phase::Revealing { answer, points } => {
+                self.draw_status(screen, locale);
+                self.draw_reveal(screen, locale, answer, points);
+
+                let last = self.question + 1 == self.quiz.questions.len();
+                let button = locale.text(if last { "quiz.results" } else { "quiz.next" });
+                let hovered = draw_text_box(screen, screen.width(), screen.height(), &button, 0, 8, mouse_position, clicked);
+
+                if (hovered && clicked) || input.is_action_pressed(Action::Confirm) {
+                    if last {
+                        progress.complete(self.quiz.door, Outcome::Score(self.score));
+                        self.phase = Phase::Finished;
+                    } else {
+                        self.ask(self.question + 1, rng);
+                    }
+                }
+            }
+            Phase::Finished => {
+                self.draw_summary(screen, locale);
+
+                let hovered = draw_text_box(screen, screen.width(), screen.height(), &locale.text("common.back_to_calendar"), 0, 4, mouse_position, clicked);
+                if (hovered && clicked) || input.is_action_pressed(Action::Confirm) {
+                    return Some(StateChange::Replace(Box::new(TransitionState::new(Box::new(MainState::new()), None))));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn fixed_update(&mut self, ctx: &mut Context, dt: f64) {
+        let Context { screen, rng, .. } = ctx;
+
+        snowflakes::update(rng, &mut self.snowflakes, screen.width(), screen.height(), self.time, dt);
+    }
+
+    fn exit(&mut self, _ctx: &mut Context) {
+    }
+
+    /// Picks up changes to the quiz file, asking the current question again.
+    /// The quiz stays as it was if its door no longer has a quiz.
+    fn reload(&mut self, ctx: &mut Context) {
+        let Some(Day::Quiz(quiz)) = ctx.days.get(self.quiz.door).cloned() else {
+            return;
+        };
+        self.quiz = quiz;
+        self.snowflakes.clear();
+        self.enter(ctx);
+
+        if !matches!(self.phase, Phase::Finished) {
+            self.ask(self.question.min(self.quiz.questions.len() - 1), &mut ctx.rng);
+        }
+    }
+
+    fn resize(&mut self, ctx: &mut Context, width: u16, height: u16) {
+        if self.has_effect(Effect::Snow) {
+            self.snowflakes = snowflakes::create(&mut ctx.rng, width, height);
+        }
+    }
+
+    fn focus_lost(&mut self, _ctx: &mut Context) -> Option<StateChange> {
+        if matches!(self.phase, Phase::Asking { .. }) {
+            return Some(StateChange::Push(Box::new(PauseState::new())));
+        }
+        None
+    }
+}
+
+/// Draws how much of the time to answer is left, as a bar that goes from
+/// green to red below the answers.
+fn draw_timer(screen: &mut Screen, fraction: f64, time_left: f64) {
+    let filled = (fraction.clamp(0.0, 1.0) * TIMER_WIDTH as f64).ceil() as u16;
+    let color = Color::Rgb {
+        r: (255.0 * (1.0 - fraction)) as u8,
+        g: (255.0 * fraction) as u8,
+        b: 0,
+    };
+
+    let x = screen.width().saturating_sub(TIMER_WIDTH) / 2;
+    let y = screen.height() / 2 + 3;
+    for i in 0..TIMER_WIDTH {
+        let c = if i < filled { '█' } else { '░' };
+        screen.set_cell(x + i, y, c, color);
+    }
+    screen.draw_text(x + TIMER_WIDTH + 2, y, &format!("{:.0} s", time_left.ceil()), Color::White);
+}
+
+/// Splits `text` into lines of at most `width` characters, breaking between
+/// words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn create_particles() -> Vec<Particle> {
+    let mut particles = Vec::new();
+    for i in 0..3 {
+        particles.push(Particle {
+            x: i as f64 * 2.0,
+            y: i as f64 * 2.0,
+            sprite: 'Z',
+        });
+    }
+    particles
+}
+
+fn draw_particles(screen: &mut Screen, particles: &mut [Particle], x: i16, y: i16, phase: f64, dt: f64) {
+    let particle_amplitude = 4.0;
+    for particle in particles.iter_mut() {
+        particle.x += (phase * 2.0 + particle.y).sin() * particle_amplitude * dt;
+        screen.set_cell_safe(x + particle.x as i16, y + particle.y as i16, particle.sprite, crossterm::style::Color::White);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use crate::context::testing::Harness;
+    use super::*;
+
+    fn quiz_state(harness: &mut Harness) -> QuizState {
+        let Some(Day::Quiz(quiz)) = harness.ctx.days.get(1).cloned() else {
+            panic!("door 1 has no quiz");
+        };
+        QuizState::new(quiz, &mut harness.ctx.rng)
+    }
+
+    fn time_left(state: &QuizState) -> f64 {
+        match state.phase {
+            Phase::Asking { time_left } => time_left,
+            _ => panic!("not asking"),
+        }
+    }
+
+    #[test]
+    fn pausing_stops_the_timer() {
+        let mut harness = Harness::new((121, 40));
+        let mut state = quiz_state(&mut harness);
+        state.enter(&mut harness.ctx);
+
+        harness.ctx.input.update(0.5).unwrap();
+        assert!(state.update(&mut harness.ctx, 0.5).is_none());
+        let before = time_left(&state);
+
+        harness.send(Event::Key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)));
+        harness.ctx.input.update(0.5).unwrap();
+        assert!(matches!(state.update(&mut harness.ctx, 0.5), Some(StateChange::Push(_))));
+        assert_eq!(time_left(&state), before);
+    }
+
+    #[test]
+    fn losing_focus_pauses_only_while_asking() {
+        let mut harness = Harness::new((121, 40));
+        let mut state = quiz_state(&mut harness);
+        state.enter(&mut harness.ctx);
+
+        assert!(matches!(state.focus_lost(&mut harness.ctx), Some(StateChange::Push(_))));
+
+        state.answer(None, 0.0);
+        assert!(state.focus_lost(&mut harness.ctx).is_none());
+    }
+}
@@ -1,8 +1,8 @@
 use std::time::Duration;
-use crate::input::Input;
-use crate::screen::{Cell, Screen};
-use crate::state_machine::State;
-use crate::transition::{Transition, TransitionDirection};
+use crate::context::Context;
+use crate::screen::Cell;
+use crate::state_machine::{State, StateChange};
+use crate::transition::{Easing, Transition, TransitionDirection, TransitionEffect};
 
 pub struct TransitionState {
     next_state: Option<Box<dyn State>>,
@@ -11,41 +11,58 @@ pub struct TransitionState {
 }
 
 impl TransitionState {
+    /// Transitions to `next_state` with the `Radial` effect. Starting in the
+    /// `Out` direction skips covering up the current screen.
     pub fn new(next_state: Box<dyn State>, state: Option<TransitionDirection>) -> Self {
         TransitionState {
             next_state: Some(next_state),
-            transition: Transition::new(Duration::from_secs(2), state),
+            transition: Transition::new(state),
             prev_cells: vec![],
         }
     }
+
+    pub fn effect(mut self, effect: impl TransitionEffect + 'static) -> Self {
+        self.transition = self.transition.effect(effect);
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.transition = self.transition.duration(duration);
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.transition = self.transition.easing(easing);
+        self
+    }
 }
 
 impl State for TransitionState {
-    fn enter(&mut self, screen: &mut Screen, _input: &mut Input) {
-        self.transition.resize(screen.width(), screen.height());
+    fn enter(&mut self, ctx: &mut Context) {
+        self.transition.resize(&mut ctx.rng, ctx.screen.width(), ctx.screen.height());
         if self.transition.state().is_none() {
-            self.transition.change_state(TransitionDirection::In);
+            let direction = if self.transition.covers() { TransitionDirection::In } else { TransitionDirection::Out };
+            self.transition.change_state(direction);
         }
-        self.prev_cells = screen.clone_buffer();
+        self.prev_cells = ctx.screen.clone_buffer();
         
         if let Some(next_state) = self.next_state.as_mut() {
-            next_state.enter(screen, _input);
+            next_state.enter(ctx);
         }
     }
 
-    fn update(&mut self, screen: &mut Screen, _input: &mut Input, dt: f64) -> Option<Box<dyn State>> {
-        
-        if let Some((width, height)) = _input.resized() {
-            self.transition.resize(width, height);
+    fn update(&mut self, ctx: &mut Context, dt: f64) -> Option<StateChange> {
+        if !ctx.config.display.transitions {
+            return self.next_state.take().map(StateChange::Replace);
         }
-        
+
         match self.transition.state() {
             Some(TransitionDirection::In) => {
-                let done = self.transition.update(screen, dt);
+                let done = self.transition.update(dt);
 
                 for (i, cell) in self.prev_cells.iter().enumerate() {
-                    let pos = screen.index_to_xy(i);
-                    screen.set_cell(pos.0, pos.1, cell.rune, cell.color);
+                    let pos = ctx.screen.index_to_xy(i);
+                    ctx.screen.set_cell(pos.0, pos.1, cell.rune, cell.style);
                 }
 
                 if done {
@@ -53,24 +70,67 @@ impl State for TransitionState {
                 }
             }
             Some(TransitionDirection::Out) => {
-                let done = self.transition.update(screen, dt);
+                let done = self.transition.update(dt);
                 let next_state = self.next_state.as_mut().unwrap();
 
-                if let Some(next_state) = next_state.update(screen, _input, dt) {
-                    return Some(next_state);
+                if let Some(change) = next_state.update(ctx, dt) {
+                    return Some(change);
                 }
 
                 if done {
-                    return self.next_state.take();
+                    return self.next_state.take().map(StateChange::Replace);
                 }
             }
             None => {}
         }
 
-        self.transition.draw(screen);
+        self.transition.draw(&mut ctx.screen, &self.prev_cells);
         None
     }
 
-    fn exit(&mut self, _screen: &mut Screen, _input: &mut Input) {
+    fn fixed_update(&mut self, ctx: &mut Context, dt: f64) {
+        if let Some(TransitionDirection::Out) = self.transition.state() {
+            if let Some(next_state) = self.next_state.as_mut() {
+                next_state.fixed_update(ctx, dt);
+            }
+        }
+    }
+
+    fn exit(&mut self, _ctx: &mut Context) {
+    }
+
+    fn resize(&mut self, ctx: &mut Context, width: u16, height: u16) {
+        self.transition.resize(&mut ctx.rng, width, height);
+        if let Some(next_state) = self.next_state.as_mut() {
+            next_state.resize(ctx, width, height);
+        }
+    }
+
+    fn reload(&mut self, ctx: &mut Context) {
+        if let Some(next_state) = self.next_state.as_mut() {
+            next_state.reload(ctx);
+        }
+    }
+
+    // The next state has already been entered, so it hears about focus
+    // changes and pauses as if it were on top.
+    fn focus_lost(&mut self, ctx: &mut Context) -> Option<StateChange> {
+        self.next_state.as_mut().and_then(|next_state| next_state.focus_lost(ctx))
+    }
+
+    fn focus_gained(&mut self, ctx: &mut Context) -> Option<StateChange> {
+        self.next_state.as_mut().and_then(|next_state| next_state.focus_gained(ctx))
+    }
+
+    fn pause(&mut self, ctx: &mut Context) {
+        if let Some(next_state) = self.next_state.as_mut() {
+            next_state.pause(ctx);
+        }
+    }
+
+    fn resume(&mut self, ctx: &mut Context) {
+        if let Some(next_state) = self.next_state.as_mut() {
+            next_state.resume(ctx);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+/// The most simulation time a single frame is allowed to catch up on. Without
+/// a cap, a long stall (like a suspended terminal) would make the simulation
+/// run thousands of steps in one frame.
+const MAX_CATCH_UP: f64 = 0.25;
+
+/// Keeps the main loop at a target frame rate by sleeping away whatever is
+/// left of each frame.
+pub struct FrameLimiter {
+    frame_time: Option<Duration>,
+    frame_start: Instant,
+}
+
+impl FrameLimiter {
+    /// `None` disables the limiter, and the loop runs as fast as it can.
+    pub fn new(target_fps: Option<u32>) -> FrameLimiter {
+        FrameLimiter {
+            frame_time: target_fps.filter(|fps| *fps > 0).map(|fps| Duration::from_secs_f64(1.0 / fps as f64)),
+            frame_start: Instant::now(),
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        match self.frame_time {
+            Some(frame_time) => frame_time.saturating_sub(self.frame_start.elapsed()),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Sleeps until the current frame has lasted its full frame time, and
+    /// starts the next one.
+    pub fn wait(&mut self) {
+        let remaining = self.remaining();
+        if !remaining.is_zero() {
+            std::thread::sleep(remaining);
+        }
+
+        self.frame_start = Instant::now();
+    }
+}
+
+/// Splits the variable frame time into steps of a fixed length, so simulations
+/// advance the same way regardless of the frame rate.
+pub struct FixedTimestep {
+    step: f64,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub fn new(step: f64) -> FixedTimestep {
+        FixedTimestep {
+            step,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// Adds the time of a frame, and returns how many fixed steps should be
+    /// simulated for it. Leftover time is carried over to the next frame.
+    pub fn advance(&mut self, dt: f64) -> u32 {
+        self.accumulator = (self.accumulator + dt).min(MAX_CATCH_UP);
+
+        let steps = (self.accumulator / self.step).floor();
+        self.accumulator -= steps * self.step;
+        steps as u32
+    }
+}
+
+/// The game's own time, advanced by the frame time instead of read from the
+/// system clock, so it runs the same way when a session is replayed.
+pub struct Clock {
+    time: f64,
+    frame: u64,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            time: 0.0,
+            frame: 0,
+        }
+    }
+
+    /// Seconds since the game started.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// How many frames have been started since the game started.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn tick(&mut self, dt: f64) {
+        self.time += dt;
+        self.frame += 1;
+    }
+}
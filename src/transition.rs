@@ -1,99 +1,398 @@
 use std::time::Duration;
 use crossterm::style;
-use crate::screen::Screen;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::screen::{Cell, Screen, Style};
 
-struct Cell {
-    x: u16,
-    y: u16,
-    alive: bool,
-}
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum TransitionDirection {
+    /// Covering up the previous screen.
     In,
+    /// Revealing the next state.
     Out,
 }
 
+/// How the progress of a transition speeds up and slows down over time.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the time elapsed, from 0 to 1, to how far along the effect is.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// How a transition looks. Most effects cover up the previous screen in the
+/// `In` phase and uncover the next state in the `Out` phase, drawing over
+/// whatever is on the screen at the time.
+pub trait TransitionEffect {
+    /// Called before the effect is first drawn, and whenever the screen
+    /// changes size.
+    fn resize(&mut self, _rng: &mut StdRng, _width: u16, _height: u16) {}
+
+    /// Whether the effect covers the screen between the previous screen and
+    /// the next state. Effects that go straight from one to the other only
+    /// have the `Out` phase.
+    fn covers(&self) -> bool {
+        true
+    }
+
+    /// Draws the effect `progress` of the way through the phase, from 0 to 1.
+    /// `previous` is the screen as it was when the transition started.
+    fn draw(&self, screen: &mut Screen, previous: &[Cell], direction: TransitionDirection, progress: f64);
+}
+
+const DEFAULT_DURATION: Duration = Duration::from_millis(1200);
+
 pub struct Transition {
-    cells: Vec<Cell>,
+    effect: Box<dyn TransitionEffect>,
     duration: Duration,
+    easing: Easing,
     timer: f64,
     state: Option<TransitionDirection>,
 }
 
 impl Transition {
-    pub fn new(duration: Duration, state: Option<TransitionDirection>) -> Transition {
+    pub fn new(state: Option<TransitionDirection>) -> Transition {
         Transition {
-            cells: Vec::new(),
-            duration,
+            effect: Box::new(Radial),
+            duration: DEFAULT_DURATION,
+            easing: Easing::EaseInOut,
             timer: 0.0,
             state,
         }
     }
 
-    pub fn resize(&mut self, width: u16, height: u16) {
-        self.cells = (0..width)
-            .flat_map(|x| (0..height).map(move |y| Cell { x, y, alive: false }))
-            .collect();
+    pub fn effect(mut self, effect: impl TransitionEffect + 'static) -> Transition {
+        self.effect = Box::new(effect);
+        self
+    }
+
+    /// How long the whole transition takes, split between its phases.
+    pub fn duration(mut self, duration: Duration) -> Transition {
+        self.duration = duration;
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Transition {
+        self.easing = easing;
+        self
+    }
+
+    pub fn resize(&mut self, rng: &mut StdRng, width: u16, height: u16) {
+        self.effect.resize(rng, width, height);
+    }
+
+    pub fn covers(&self) -> bool {
+        self.effect.covers()
     }
 
     pub fn state(&self) -> Option<TransitionDirection> {
-        match self.state {
-            Some(TransitionDirection::In) => Some(TransitionDirection::In),
-            Some(TransitionDirection::Out) => Some(TransitionDirection::Out),
-            None => None,
-        }
+        self.state
     }
 
     pub fn change_state(&mut self, state: TransitionDirection) {
         self.state = Some(state);
         self.timer = 0.0;
+    }
 
-        for cell in &mut self.cells {
-            cell.alive = match self.state {
-                Some(TransitionDirection::In) => false,
-                Some(TransitionDirection::Out) => true,
-                None => false,
-            };
+    fn phase_duration(&self) -> f64 {
+        let duration = self.duration.as_secs_f64();
+        if self.effect.covers() {
+            duration / 2.0
+        } else {
+            duration
         }
     }
 
-    pub fn update(&mut self, screen: &mut Screen, dt: f64) -> bool {
+    /// Advances the current phase, and returns whether it is over.
+    pub fn update(&mut self, dt: f64) -> bool {
         self.timer += dt;
+        self.timer >= self.phase_duration()
+    }
+
+    pub fn draw(&self, screen: &mut Screen, previous: &[Cell]) {
+        let Some(direction) = self.state else {
+            return;
+        };
 
-        let normalized_timer = self.timer / self.duration.as_secs_f64();
+        let phase_duration = self.phase_duration();
+        let t = if phase_duration > 0.0 { self.timer / phase_duration } else { 1.0 };
+        self.effect.draw(screen, previous, direction, self.easing.apply(t));
+    }
+}
+
+/// Covers the screen from the middle and out, and uncovers it the same way.
+pub struct Radial;
+
+impl TransitionEffect for Radial {
+    fn draw(&self, screen: &mut Screen, _previous: &[Cell], direction: TransitionDirection, progress: f64) {
         let center_x = screen.width() as f64 / 2.0;
         let center_y = screen.height() as f64 / 2.0;
 
-        for cell in &mut self.cells {
-            let dx = (cell.x as f64 - center_x) / center_x;
-            let dy = (cell.y as f64 - center_y) / center_y;
-            let distance = (dx * dx + dy * dy).sqrt();
+        draw_mask(screen, direction, progress, |x, y| {
+            let dx = (x as f64 - center_x) / center_x;
+            let dy = (y as f64 - center_y) / center_y;
+            (dx * dx + dy * dy).sqrt() / 1.5
+        }, brick);
+    }
+}
+
+/// Closes in on the middle of the screen like a camera iris, and opens up
+/// from the middle again.
+pub struct Iris;
+
+impl TransitionEffect for Iris {
+    fn draw(&self, screen: &mut Screen, _previous: &[Cell], direction: TransitionDirection, progress: f64) {
+        let center_x = screen.width() as f64 / 2.0;
+        let center_y = screen.height() as f64 / 2.0;
+
+        draw_mask(screen, direction, progress, |x, y| {
+            // Cells are about twice as tall as they are wide, so the iris is
+            // squashed to look round.
+            let dx = (x as f64 - center_x) / center_x;
+            let dy = (y as f64 - center_y) / center_x * 2.0;
+            let distance = ((dx * dx + dy * dy).sqrt() / 1.5).min(1.0);
+            match direction {
+                TransitionDirection::In => 1.0 - distance,
+                TransitionDirection::Out => distance,
+            }
+        }, |_, _| ('█', style::Color::Black.into()));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WipeDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// Sweeps over the screen from one edge to the other.
+pub struct Wipe {
+    direction: WipeDirection,
+}
+
+impl Wipe {
+    /// Sweeps from left to right.
+    pub fn horizontal() -> Wipe {
+        Wipe { direction: WipeDirection::Horizontal }
+    }
+
+    /// Sweeps from top to bottom.
+    pub fn vertical() -> Wipe {
+        Wipe { direction: WipeDirection::Vertical }
+    }
+}
+
+impl TransitionEffect for Wipe {
+    fn draw(&self, screen: &mut Screen, _previous: &[Cell], direction: TransitionDirection, progress: f64) {
+        let width = screen.width() as f64;
+        let height = screen.height() as f64;
+
+        draw_mask(screen, direction, progress, |x, y| match self.direction {
+            WipeDirection::Horizontal => x as f64 / width,
+            WipeDirection::Vertical => y as f64 / height,
+        }, brick);
+    }
+}
+
+/// Covers and uncovers the screen one cell at a time, in random order.
+pub struct Dissolve {
+    thresholds: Vec<f64>,
+}
+
+impl Dissolve {
+    pub fn new() -> Dissolve {
+        Dissolve { thresholds: Vec::new() }
+    }
+}
+
+impl TransitionEffect for Dissolve {
+    fn resize(&mut self, rng: &mut StdRng, width: u16, height: u16) {
+        self.thresholds = random_thresholds(rng, width, height);
+    }
+
+    fn draw(&self, screen: &mut Screen, _previous: &[Cell], direction: TransitionDirection, progress: f64) {
+        let width = screen.width();
+        draw_mask(screen, direction, progress, |x, y| {
+            threshold_at(&self.thresholds, width, x, y)
+        }, brick);
+    }
+}
+
+/// Blends the previous screen into the next state, without covering the
+/// screen in between.
+pub struct CrossFade {
+    thresholds: Vec<f64>,
+}
+
+impl CrossFade {
+    pub fn new() -> CrossFade {
+        CrossFade { thresholds: Vec::new() }
+    }
+}
 
-            let distance = distance / 1.5;
+impl TransitionEffect for CrossFade {
+    fn resize(&mut self, rng: &mut StdRng, width: u16, height: u16) {
+        self.thresholds = random_thresholds(rng, width, height);
+    }
 
-            cell.alive = match self.state {
-                Some(TransitionDirection::In) => distance < normalized_timer,
-                Some(TransitionDirection::Out) => distance > normalized_timer,
-                None => false,
+    fn covers(&self) -> bool {
+        false
+    }
+
+    fn draw(&self, screen: &mut Screen, previous: &[Cell], _direction: TransitionDirection, progress: f64) {
+        if previous.len() != screen.width() as usize * screen.height() as usize {
+            return;
+        }
+
+        for (i, old) in previous.iter().enumerate() {
+            let (x, y) = screen.index_to_xy(i);
+            let new = screen.get_cell(x, y);
+
+            // Each cell switches character at a random point, while the
+            // colors fade smoothly from one screen to the other.
+            let rune = if threshold_at(&self.thresholds, screen.width(), x, y) < progress {
+                new.rune
+            } else {
+                old.rune
+            };
+            let style = Style {
+                foreground: blend(old.style.foreground, new.style.foreground, progress),
+                background: blend(old.style.background, new.style.background, progress),
+                ..new.style
             };
+            screen.set_cell(x, y, rune, style);
         }
+    }
+}
+
+/// Snow falls down and piles up over the screen, then falls away again.
+pub struct SnowCurtain {
+    /// How much later than the others each column is covered, from 0 to 1.
+    delays: Vec<f64>,
+}
+
+impl SnowCurtain {
+    pub fn new() -> SnowCurtain {
+        SnowCurtain { delays: Vec::new() }
+    }
+}
+
+impl TransitionEffect for SnowCurtain {
+    fn resize(&mut self, rng: &mut StdRng, width: u16, _height: u16) {
+        self.delays = (0..width).map(|_| rng.random::<f64>()).collect();
+    }
+
+    fn draw(&self, screen: &mut Screen, _previous: &[Cell], direction: TransitionDirection, progress: f64) {
+        const FLAKES: [char; 4] = ['*', '.', '❄', '+'];
+        let height = screen.height() as f64;
 
-        self.timer >= self.duration.as_secs_f64()
+        draw_mask(screen, direction, progress, |x, y| {
+            let delay = self.delays.get(x as usize).copied().unwrap_or(0.0);
+            (y as f64 / height) * 0.8 + delay * 0.2
+        }, |x, y| {
+            let rune = FLAKES[(x as usize * 7 + y as usize * 13) % FLAKES.len()];
+            (rune, style::Color::White.into())
+        });
+    }
+}
+
+/// Draws the cells an effect covers. A cell is covered while the phase is
+/// past its `threshold` coming in, and until the phase passes it going out.
+fn draw_mask(
+    screen: &mut Screen,
+    direction: TransitionDirection,
+    progress: f64,
+    threshold: impl Fn(u16, u16) -> f64,
+    cover: impl Fn(u16, u16) -> (char, Style),
+) {
+    for y in 0..screen.height() {
+        for x in 0..screen.width() {
+            let covered = match direction {
+                TransitionDirection::In => threshold(x, y) < progress,
+                TransitionDirection::Out => threshold(x, y) > progress,
+            };
+            if covered {
+                let (rune, style) = cover(x, y);
+                screen.set_cell(x, y, rune, style);
+            }
+        }
     }
+}
+
+fn brick(x: u16, y: u16) -> (char, Style) {
+    const PATTERN: [[char; 8]; 4] = [
+        [' ', '_', '|', '_', ' ', ' ', ' ', ' '],
+        [' ', ' ', '|', ' ', ' ', ' ', ' ', ' '],
+        [' ', ' ', ' ', ' ', '_', '|', '_', ' '],
+        [' ', ' ', ' ', ' ', ' ', '|', ' ', ' ']
+    ];
+
+    let index = (y % PATTERN.len() as u16) as usize;
+    let rune = PATTERN[index][x as usize % PATTERN[index].len()];
+    (rune, style::Color::White.into())
+}
 
-    pub fn draw(&self, screen: &mut Screen) {
-        const PATTERN: [[char; 8]; 4] = [
-            [' ', '_', '|', '_', ' ', ' ', ' ', ' '],
-            [' ', ' ', '|', ' ', ' ', ' ', ' ', ' '],
-            [' ', ' ', ' ', ' ', '_', '|', '_', ' '],
-            [' ', ' ', ' ', ' ', ' ', '|', ' ', ' ']
-        ];
+fn random_thresholds(rng: &mut StdRng, width: u16, height: u16) -> Vec<f64> {
+    (0..width as usize * height as usize).map(|_| rng.random::<f64>()).collect()
+}
+
+fn threshold_at(thresholds: &[f64], width: u16, x: u16, y: u16) -> f64 {
+    thresholds.get(y as usize * width as usize + x as usize).copied().unwrap_or(0.0)
+}
 
-        for cell in &self.cells {
-            if cell.alive {
-                let index = (cell.y % PATTERN.len() as u16) as usize;
-                let rune = PATTERN[index][cell.x as usize % PATTERN[index].len()];
-                screen.set_cell(cell.x, cell.y, rune, style::Color::White);
+/// The color `t` of the way from `from` to `to`. Colors that have no known RGB
+/// value switch halfway.
+fn blend(from: style::Color, to: style::Color, t: f64) -> style::Color {
+    match (rgb(from), rgb(to)) {
+        (Some(from), Some(to)) => {
+            let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            style::Color::Rgb {
+                r: lerp(from.0, to.0),
+                g: lerp(from.1, to.1),
+                b: lerp(from.2, to.2),
             }
         }
+        _ if t < 0.5 => from,
+        _ => to,
     }
-}
\ No newline at end of file
+}
+
+fn rgb(color: style::Color) -> Option<(u8, u8, u8)> {
+    use style::Color::*;
+
+    match color {
+        Black => Some((0, 0, 0)),
+        DarkGrey => Some((128, 128, 128)),
+        Red => Some((255, 0, 0)),
+        DarkRed => Some((128, 0, 0)),
+        Green => Some((0, 255, 0)),
+        DarkGreen => Some((0, 128, 0)),
+        Yellow => Some((255, 255, 0)),
+        DarkYellow => Some((128, 128, 0)),
+        Blue => Some((0, 0, 255)),
+        DarkBlue => Some((0, 0, 128)),
+        Magenta => Some((255, 0, 255)),
+        DarkMagenta => Some((128, 0, 128)),
+        Cyan => Some((0, 255, 255)),
+        DarkCyan => Some((0, 128, 128)),
+        White => Some((255, 255, 255)),
+        Grey => Some((192, 192, 192)),
+        Rgb { r, g, b } => Some((r, g, b)),
+        Reset | AnsiValue(_) => None,
+    }
+}
+
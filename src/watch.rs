@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Seconds between each look at the files.
+const POLL_INTERVAL: f64 = 0.5;
+
+/// Notices when files are added to, changed in or removed from some folders,
+/// by looking at their modification times every now and then.
+pub struct Watcher {
+    dirs: Vec<PathBuf>,
+    /// The modification time of every file, from the last look.
+    files: BTreeMap<PathBuf, SystemTime>,
+    /// Seconds until the next look.
+    countdown: f64,
+}
+
+impl Watcher {
+    pub fn new(dirs: Vec<PathBuf>) -> Watcher {
+        let files = scan(&dirs);
+        Watcher {
+            dirs,
+            files,
+            countdown: POLL_INTERVAL,
+        }
+    }
+
+    /// Whether any file changed since the last time this returned `true`.
+    /// Only looks at the files once every `POLL_INTERVAL`.
+    pub fn poll(&mut self, dt: f64) -> bool {
+        self.countdown -= dt;
+        if self.countdown > 0.0 {
+            return false;
+        }
+        self.countdown = POLL_INTERVAL;
+
+        let files = scan(&self.dirs);
+        if files == self.files {
+            return false;
+        }
+        self.files = files;
+        true
+    }
+}
+
+/// The modification time of every file in `dirs`. Folders and files that
+/// can't be read are left out, so they count as changed once they can.
+fn scan(dirs: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    let mut files = BTreeMap::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                files.insert(entry.path(), modified);
+            }
+        }
+    }
+    files
+}